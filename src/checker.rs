@@ -0,0 +1,2065 @@
+use std::collections::HashMap;
+
+use crate::{
+    diagnostic::Diagnostic,
+    expression::{AssignOperator, BinaryOperator, Expression, LogicalOperator, PostfixOperator, TypeOperator, UnaryOperator},
+    pattern::Pattern,
+    statement::{Class, Enum, Field, Function, Import, Mutability, Parameter, ParameterKind, Statement},
+    types::Type,
+};
+
+/// What a name in scope refers to.
+#[derive(Clone)]
+enum Symbol {
+    Variable(Type),
+    Function(Signature),
+    Class(ClassInfo),
+    /// The type an extension applies to and the methods it adds
+    Extension(Type, HashMap<String, Signature>),
+    /// A module imported under an alias and the names it exports
+    Module(String, Vec<String>),
+}
+
+#[derive(Clone)]
+struct Signature {
+    type_parameters: Vec<String>,
+    /// positional parameters, the first `required` of which must be passed
+    parameters: Vec<(String, Type)>,
+    required: usize,
+    /// named parameters and whether they must be passed
+    named: Vec<(String, Type, bool)>,
+    /// the parameter collecting the positional arguments left over, with the type of its elements
+    rest: Option<(String, Type)>,
+    return_type: Type,
+}
+
+impl Signature {
+    fn substitute(&self, bindings: &HashMap<String, Type>) -> Signature {
+        Signature {
+            type_parameters: self.type_parameters.clone(),
+            parameters: self
+                .parameters
+                .iter()
+                .map(|(name, annotation)| (name.clone(), annotation.substitute(bindings)))
+                .collect(),
+            required: self.required,
+            named: self
+                .named
+                .iter()
+                .map(|(name, annotation, required)| (name.clone(), annotation.substitute(bindings), *required))
+                .collect(),
+            rest: self
+                .rest
+                .as_ref()
+                .map(|(name, annotation)| (name.clone(), annotation.substitute(bindings))),
+            return_type: self.return_type.substitute(bindings),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ClassInfo {
+    type_parameters: Vec<String>,
+    superclass: Option<Type>,
+    fields: HashMap<String, Type>,
+    methods: HashMap<String, Signature>,
+    /// names of the values of an enum in declaration order, empty for a class
+    values: Vec<String>,
+    /// methods called on the class itself rather than on an instance, as `Fiber.yield(value)`
+    statics: HashMap<String, Signature>,
+}
+
+enum Member {
+    Field(Type),
+    Method(Signature),
+}
+
+/// Reports type mismatches in annotated code before it runs. Anything left
+/// unannotated is `dynamic` and accepted as is, so annotations can be adopted
+/// gradually.
+pub struct Checker {
+    scopes: Vec<HashMap<String, Symbol>>,
+    /// names and declared return types of the functions being checked, innermost last
+    functions: Vec<(String, Type)>,
+    /// element types of the functions being checked that are generators, `None`
+    /// for the others, innermost last
+    yields: Vec<Option<Type>>,
+    /// types of `this` in the classes being checked, innermost last
+    classes: Vec<Type>,
+    /// line of the statement being checked
+    line: i32,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker {
+    pub fn new() -> Checker {
+        Checker {
+            scopes: Vec::new(),
+            functions: Vec::new(),
+            yields: Vec::new(),
+            classes: Vec::new(),
+            line: 1,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, message: String) {
+        self.diagnostics.push(Diagnostic::new(self.line, message));
+    }
+
+    fn declare(&mut self, name: &str, symbol: Symbol) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), symbol);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Symbol> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+}
+
+pub fn check_program(checker: &mut Checker, statements: &[Statement]) {
+    checker.scopes.push(builtins());
+    check_block(checker, statements);
+    checker.scopes.pop();
+}
+
+/// Classes provided by the runtime, in scope around the program.
+fn builtins() -> HashMap<String, Symbol> {
+    let method = |parameters: Vec<(&str, Type)>, required: usize, return_type: Type| Signature {
+        type_parameters: Vec::new(),
+        parameters: parameters
+            .into_iter()
+            .map(|(name, annotation)| (name.to_string(), annotation))
+            .collect(),
+        required,
+        named: Vec::new(),
+        rest: None,
+        return_type,
+    };
+
+    // `Fiber(body)` runs `body` on a stack of its own: `resume(value)` runs it until
+    // it calls `Fiber.yield(value)` or returns, and evaluates to that value
+    let fiber = ClassInfo {
+        type_parameters: Vec::new(),
+        superclass: None,
+        fields: HashMap::from([
+            ("status".to_string(), Type::String),
+            ("isDone".to_string(), Type::Bool),
+        ]),
+        methods: HashMap::from([
+            ("init".to_string(), method(vec![("body", Type::Function)], 1, Type::Dynamic)),
+            ("resume".to_string(), method(vec![("value", Type::Dynamic)], 0, Type::Dynamic)),
+        ]),
+        values: Vec::new(),
+        statics: HashMap::from([(
+            "yield".to_string(),
+            method(vec![("value", Type::Dynamic)], 0, Type::Dynamic),
+        )]),
+    };
+
+    HashMap::from([("Fiber".to_string(), Symbol::Class(fiber))])
+}
+
+fn check_block(checker: &mut Checker, statements: &[Statement]) {
+    checker.scopes.push(HashMap::new());
+
+    // functions, classes and extensions can be used before the statement declaring them
+    for statement in statements.iter().map(exported) {
+        match statement {
+            Statement::Function(function) => {
+                checker.declare(&function.name, Symbol::Function(signature(function)));
+            }
+            Statement::Class(class) => checker.declare(&class.name, Symbol::Class(class_info(class))),
+            Statement::Enum(declaration) => {
+                checker.declare(&declaration.name, Symbol::Class(enum_info(declaration)));
+            }
+            Statement::Extension(extension) => {
+                let methods = extension
+                    .methods
+                    .iter()
+                    .map(|method| (method.name.clone(), signature(method)))
+                    .collect();
+                checker.declare(&extension.name, Symbol::Extension(extension.on.clone(), methods));
+            }
+            Statement::Import(import) => declare_import(checker, import),
+            _ => {}
+        }
+    }
+
+    for statement in statements.iter().map(exported) {
+        if let Statement::Class(class) = statement {
+            check_inheritance(checker, class);
+        }
+    }
+
+    for statement in statements {
+        check_statement(checker, statement);
+    }
+
+    checker.scopes.pop();
+}
+
+/// The declaration an `export` statement marks, any other statement itself.
+fn exported(statement: &Statement) -> &Statement {
+    match statement {
+        Statement::Export(declaration) => declaration,
+        statement => statement,
+    }
+}
+
+/// The values a module exports are only known at run time, an alias checks
+/// that the names used on it are exported. A module the loader has not loaded
+/// declares nothing it can check.
+fn declare_import(checker: &mut Checker, import: &Import) {
+    match (&import.alias, &import.exports) {
+        (Some(alias), Some(exports)) => {
+            checker.declare(alias, Symbol::Module(import.path.clone(), exports.clone()));
+        }
+        (Some(alias), None) => checker.declare(alias, Symbol::Variable(Type::Dynamic)),
+        (None, Some(exports)) => {
+            for name in exports {
+                checker.declare(name, Symbol::Variable(Type::Dynamic));
+            }
+        }
+        (None, None) => {}
+    }
+}
+
+fn signature(function: &Function) -> Signature {
+    let mut parameters = Vec::new();
+    let mut named = Vec::new();
+    let mut rest = None;
+    for parameter in &function.parameters {
+        let annotation = parameter.annotation.clone().unwrap_or(Type::Dynamic);
+        match parameter.kind {
+            ParameterKind::Positional | ParameterKind::Optional => parameters.push((parameter.name.clone(), annotation)),
+            ParameterKind::Named => named.push((parameter.name.clone(), annotation, false)),
+            ParameterKind::Required => named.push((parameter.name.clone(), annotation, true)),
+            ParameterKind::Rest => rest = Some((parameter.name.clone(), annotation)),
+        }
+    }
+
+    Signature {
+        type_parameters: function.type_parameters.clone(),
+        parameters,
+        required: function
+            .parameters
+            .iter()
+            .filter(|parameter| parameter.kind == ParameterKind::Positional)
+            .count(),
+        named,
+        rest,
+        return_type: match &function.return_type {
+            Some(return_type) => return_type.clone(),
+            None if function.generator => Type::Iterable,
+            None if function.asynchronous => Type::Future,
+            None => Type::Dynamic,
+        },
+    }
+}
+
+fn class_info(class: &Class) -> ClassInfo {
+    ClassInfo {
+        type_parameters: class.type_parameters.clone(),
+        superclass: class.superclass.clone(),
+        fields: class
+            .fields
+            .iter()
+            .map(|field| (field.name.clone(), field.annotation.clone().unwrap_or(Type::Dynamic)))
+            .collect(),
+        methods: class
+            .methods
+            .iter()
+            .map(|method| (method.name.clone(), signature(method)))
+            .collect(),
+        values: Vec::new(),
+        statics: HashMap::new(),
+    }
+}
+
+fn enum_info(declaration: &Enum) -> ClassInfo {
+    let mut fields: HashMap<String, Type> = declaration
+        .fields
+        .iter()
+        .map(|field| (field.name.clone(), field.annotation.clone().unwrap_or(Type::Dynamic)))
+        .collect();
+    fields.insert("name".to_string(), Type::String);
+    fields.insert("index".to_string(), Type::Int);
+
+    ClassInfo {
+        type_parameters: Vec::new(),
+        superclass: None,
+        fields,
+        methods: declaration
+            .methods
+            .iter()
+            .map(|method| (method.name.clone(), signature(method)))
+            .collect(),
+        values: declaration.values.iter().map(|value| value.name.clone()).collect(),
+        statics: HashMap::new(),
+    }
+}
+
+/// The names of the values of the enum a name refers to.
+fn lookup_enum(checker: &Checker, name: &str) -> Option<Vec<String>> {
+    match checker.lookup(name) {
+        Some(Symbol::Class(info)) if !info.values.is_empty() => Some(info.values.clone()),
+        _ => None,
+    }
+}
+
+/// The name and static methods of the class an expression names, `None` for
+/// an enum or anything but a class.
+fn lookup_statics<'a>(checker: &Checker, object: &'a Expression) -> Option<(&'a str, HashMap<String, Signature>)> {
+    match object {
+        Expression::Variable(name) => match checker.lookup(name) {
+            Some(Symbol::Class(info)) if info.values.is_empty() => Some((name, info.statics.clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The type of `this` in a class, its type parameters stand for themselves.
+fn class_type(name: &str, type_parameters: &[String]) -> Type {
+    if type_parameters.is_empty() {
+        return Type::Class(name.to_string());
+    }
+
+    let arguments = type_parameters.iter().cloned().map(Type::Parameter).collect();
+    Type::Generic(Box::new(Type::Class(name.to_string())), arguments)
+}
+
+/// Reports a superclass that is not a class or that leads back to the class,
+/// and forgets it so that member lookups always end.
+fn check_inheritance(checker: &mut Checker, class: &Class) {
+    let superclass = match &class.superclass {
+        Some(superclass) => superclass,
+        None => return,
+    };
+    checker.line = class.line;
+
+    let mut visited = vec![class.name.clone()];
+    let mut current = superclass.clone();
+    let message = loop {
+        match resolve_class(checker, &current) {
+            Some((name, _, _)) if name == class.name => {
+                break Some(format!("class '{}' can't inherit from itself", class.name));
+            }
+            Some((name, _, _)) if visited.contains(&name) => break None,
+            Some((name, info, bindings)) => {
+                visited.push(name);
+                match &info.superclass {
+                    Some(superclass) => current = superclass.substitute(&bindings),
+                    None => break None,
+                }
+            }
+            None if current == *superclass && !matches!(current, Type::Dynamic) => {
+                break Some(format!("class '{}' can't extend {}, it is not a class", class.name, superclass));
+            }
+            None => break None,
+        }
+    };
+
+    if let Some(message) = message {
+        checker.error(message);
+        if let Some(Symbol::Class(info)) = checker.scopes.last_mut().and_then(|scope| scope.get_mut(&class.name)) {
+            info.superclass = None;
+        }
+    }
+}
+
+/// Finds the class a type refers to, with its type parameters bound to the type arguments.
+fn resolve_class<'a>(checker: &'a Checker, object: &Type) -> Option<(String, &'a ClassInfo, HashMap<String, Type>)> {
+    let (name, arguments) = match object {
+        Type::Class(name) => (name, &[][..]),
+        Type::Generic(base, arguments) => match &**base {
+            Type::Class(name) => (name, &arguments[..]),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let info = match checker.lookup(name) {
+        Some(Symbol::Class(info)) => info,
+        _ => return None,
+    };
+
+    // a class used without type arguments has them all dynamic
+    let bindings = info
+        .type_parameters
+        .iter()
+        .enumerate()
+        .map(|(index, parameter)| (parameter.clone(), arguments.get(index).cloned().unwrap_or(Type::Dynamic)))
+        .collect();
+
+    Some((name.clone(), info, bindings))
+}
+
+fn superclass(checker: &Checker, object: &Type) -> Option<Type> {
+    let (_, info, bindings) = resolve_class(checker, object)?;
+    info.superclass.as_ref().map(|superclass| superclass.substitute(&bindings))
+}
+
+/// Looks a field or method up on a class type and its superclasses.
+fn find_member(checker: &Checker, object: &Type, name: &str) -> Option<Member> {
+    find_class_member(checker, object, name).or_else(|| find_extension_member(checker, object, name))
+}
+
+fn find_class_member(checker: &Checker, object: &Type, name: &str) -> Option<Member> {
+    let (_, info, bindings) = resolve_class(checker, object)?;
+
+    if let Some(field) = info.fields.get(name) {
+        return Some(Member::Field(field.substitute(&bindings)));
+    }
+    if let Some(method) = info.methods.get(name) {
+        return Some(Member::Method(method.substitute(&bindings)));
+    }
+
+    find_class_member(checker, &superclass(checker, object)?, name)
+}
+
+/// A method added to the type by an extension in scope, the innermost first.
+/// A `dynamic` value has none, its members are only known at run time.
+fn find_extension_member(checker: &Checker, object: &Type, name: &str) -> Option<Member> {
+    if *object == Type::Dynamic {
+        return None;
+    }
+
+    checker.scopes.iter().rev().find_map(|scope| {
+        scope.values().find_map(|symbol| match symbol {
+            Symbol::Extension(on, methods) if is_subtype(checker, object, on) => {
+                methods.get(name).cloned().map(Member::Method)
+            }
+            _ => None,
+        })
+    })
+}
+
+fn check_statement(checker: &mut Checker, statement: &Statement) {
+    match statement {
+        Statement::Expression(expression, line) | Statement::Print(expression, line) => {
+            checker.line = *line;
+            check_expression(checker, expression);
+        }
+        Statement::Var(mutability, name, annotation, initializer, line) => {
+            checker.line = *line;
+            if let Some(annotation) = annotation {
+                check_annotation(checker, annotation);
+            }
+
+            let mut inferred = Type::Dynamic;
+            if let Some(initializer) = initializer {
+                let actual = check_expression(checker, initializer);
+
+                if let Some(annotation) = annotation {
+                    if !is_assignable(checker, initializer, &actual, annotation) {
+                        checker.error(format!(
+                            "can't assign a value of type {} to variable '{}' of type {}",
+                            actual, name, annotation
+                        ));
+                    }
+                }
+
+                // a variable that is never assigned again keeps the type of its value
+                if *mutability != Mutability::Var {
+                    inferred = actual;
+                }
+            }
+
+            let declared = annotation.clone().unwrap_or(inferred);
+            checker.declare(name, Symbol::Variable(declared));
+        }
+        Statement::Destructure(_, pattern, value, line) => {
+            checker.line = *line;
+            check_expression(checker, value);
+            check_pattern(checker, pattern);
+        }
+        Statement::Block(statements) => check_block(checker, statements),
+        Statement::If(condition, then_branch, else_branch) => {
+            check_expression(checker, condition);
+            check_statement(checker, then_branch);
+            if let Some(else_branch) = else_branch {
+                check_statement(checker, else_branch);
+            }
+        }
+        Statement::While(condition, body) => {
+            check_expression(checker, condition);
+            check_statement(checker, body);
+        }
+        Statement::For(initializer, condition, increment, body) => {
+            checker.scopes.push(HashMap::new());
+            if let Some(initializer) = initializer {
+                check_statement(checker, initializer);
+            }
+            if let Some(condition) = condition {
+                check_expression(checker, condition);
+            }
+            if let Some(increment) = increment {
+                check_expression(checker, increment);
+            }
+            check_statement(checker, body);
+            checker.scopes.pop();
+        }
+        Statement::ForIn(name, iterable, body, line) => {
+            checker.line = *line;
+            let iterable = check_expression(checker, iterable);
+            let element = iterated_type(checker, &iterable);
+            checker.scopes.push(HashMap::new());
+            checker.declare(name, Symbol::Variable(element));
+            check_statement(checker, body);
+            checker.scopes.pop();
+        }
+        Statement::Labeled(_, statement) => check_statement(checker, statement),
+        Statement::Break(_) | Statement::Continue(_) => {}
+        Statement::Switch(value, cases, default, line) => {
+            checker.line = *line;
+            let scrutinee = check_expression(checker, value);
+
+            let patterns = cases.iter().map(|case| (&case.pattern, case.guard.is_some()));
+            check_case_enums(checker, &scrutinee, patterns.clone());
+            if default.is_none() {
+                check_exhaustive(checker, &scrutinee, patterns.collect());
+            }
+
+            for case in cases {
+                checker.scopes.push(HashMap::new());
+                check_pattern(checker, &case.pattern);
+                if let Some(guard) = &case.guard {
+                    check_expression(checker, guard);
+                }
+                check_block(checker, &case.body);
+                checker.scopes.pop();
+            }
+
+            if let Some(default) = default {
+                check_block(checker, default);
+            }
+        }
+        Statement::Function(function) => check_function(checker, function),
+        Statement::Class(class) => {
+            checker.classes.push(class_type(&class.name, &class.type_parameters));
+            check_members(checker, &class.fields, &class.methods);
+            checker.classes.pop();
+        }
+        Statement::Extension(extension) => {
+            checker.line = extension.line;
+            check_annotation(checker, &extension.on);
+            checker.classes.push(extension.on.clone());
+            check_members(checker, &[], &extension.methods);
+            checker.classes.pop();
+        }
+        Statement::Enum(declaration) => {
+            let info = enum_info(declaration);
+            let constructor = constructor(checker, &declaration.name, &info);
+
+            for value in &declaration.values {
+                checker.line = value.line;
+                check_arguments(checker, &declaration.name, &constructor, &value.arguments, &value.named);
+            }
+
+            checker.classes.push(Type::Class(declaration.name.clone()));
+            check_members(checker, &declaration.fields, &declaration.methods);
+            checker.classes.pop();
+        }
+        Statement::Import(import) => checker.line = import.line,
+        Statement::Export(declaration) => check_statement(checker, declaration),
+        Statement::Try(statement) => {
+            check_block(checker, &statement.body);
+
+            if let Some(catch) = &statement.catch {
+                checker.scopes.push(HashMap::new());
+                checker.declare(&catch.exception, Symbol::Variable(Type::Dynamic));
+                if let Some(stack) = &catch.stack {
+                    checker.declare(stack, Symbol::Variable(Type::Dynamic));
+                }
+                check_block(checker, &catch.body);
+                checker.scopes.pop();
+            }
+
+            if let Some(finally) = &statement.finally {
+                check_block(checker, finally);
+            }
+        }
+        Statement::Assert(condition, message, line) => {
+            checker.line = *line;
+            let actual = check_expression(checker, condition);
+            if !actual.is_assignable_to(&Type::Bool) {
+                checker.error(format!("assert condition must be a bool, found {}", actual));
+            }
+
+            if let Some(message) = message {
+                check_expression(checker, message);
+            }
+        }
+        Statement::Yield(value, line) => {
+            checker.line = *line;
+            let actual = check_expression(checker, value);
+
+            if let (Some((name, _)), Some(Some(element))) = (checker.functions.last(), checker.yields.last()) {
+                let (name, element) = (name.clone(), element.clone());
+                if !is_assignable(checker, value, &actual, &element) {
+                    checker.error(format!(
+                        "can't yield a value of type {} from generator '{}' yielding {}",
+                        actual, name, element
+                    ));
+                }
+            }
+        }
+        Statement::Return(value, line) => {
+            checker.line = *line;
+
+            let actual = match value {
+                Some(value) => check_expression(checker, value),
+                None => Type::Null,
+            };
+
+            if let Some((name, expected)) = checker.functions.last().cloned() {
+                let assignable = match value {
+                    Some(value) => is_assignable(checker, value, &actual, &expected),
+                    None => actual.is_assignable_to(&expected),
+                };
+
+                if !assignable {
+                    checker.error(format!(
+                        "can't return a value of type {} from function '{}' with return type {}",
+                        actual, name, expected
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Reports the class names of an annotation that name no class in scope. An
+/// imported name is `dynamic`, it may be a class of the module.
+fn check_annotation(checker: &mut Checker, annotation: &Type) {
+    match annotation {
+        Type::Class(name) => match checker.lookup(name) {
+            Some(Symbol::Class(_)) | Some(Symbol::Variable(Type::Dynamic)) => {}
+            _ => checker.error(format!("unknown type '{}'", name)),
+        },
+        Type::Nullable(inner) => check_annotation(checker, inner),
+        Type::Generic(base, arguments) => {
+            check_annotation(checker, base);
+            for argument in arguments {
+                check_annotation(checker, argument);
+            }
+        }
+        Type::Record(positional, named) => {
+            for field in positional.iter().chain(named.iter().map(|(_, field)| field)) {
+                check_annotation(checker, field);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_members(checker: &mut Checker, fields: &[Field], methods: &[Function]) {
+    for field in fields {
+        checker.line = field.line;
+        if let Some(annotation) = &field.annotation {
+            check_annotation(checker, annotation);
+        }
+        if let Some(initializer) = &field.initializer {
+            let actual = check_expression(checker, initializer);
+
+            if let Some(annotation) = &field.annotation {
+                if !is_assignable(checker, initializer, &actual, annotation) {
+                    checker.error(format!(
+                        "can't assign a value of type {} to field '{}' of type {}",
+                        actual, field.name, annotation
+                    ));
+                }
+            }
+        }
+    }
+
+    for method in methods {
+        check_function(checker, method);
+    }
+}
+
+fn check_function(checker: &mut Checker, function: &Function) {
+    let signature = signature(function);
+
+    checker.line = function.line;
+    let annotations = function.parameters.iter().filter_map(|parameter| parameter.annotation.as_ref());
+    for annotation in annotations.chain(&function.return_type) {
+        check_annotation(checker, annotation);
+    }
+    for parameter in &function.parameters {
+        check_default(checker, parameter);
+    }
+
+    checker.scopes.push(HashMap::new());
+    for parameter in &function.parameters {
+        let annotation = match (parameter.kind, &parameter.annotation) {
+            (ParameterKind::Rest, Some(element)) => Type::Generic(Box::new(Type::List), vec![element.clone()]),
+            (ParameterKind::Rest, None) => Type::List,
+            (_, annotation) => annotation.clone().unwrap_or(Type::Dynamic),
+        };
+        checker.declare(&parameter.name, Symbol::Variable(annotation));
+    }
+
+    // a generator returns its iterable as soon as it is called, its own `return` ends
+    // the iteration, and an async function returns its future, completed by `return`
+    let (return_type, element) = match (function.generator, function.asynchronous) {
+        (true, _) => (Type::Dynamic, Some(element_type(checker, function, &signature.return_type))),
+        (false, true) => (element_type(checker, function, &signature.return_type), None),
+        (false, false) => (signature.return_type, None),
+    };
+
+    checker.functions.push((function.name.clone(), return_type));
+    checker.yields.push(element);
+    check_block(checker, &function.body);
+    checker.yields.pop();
+    checker.functions.pop();
+
+    checker.scopes.pop();
+}
+
+/// The type of the elements a generator yields or of the value an async function
+/// completes its future with, from its `Iterable<T>` or `Future<T>` return type.
+fn element_type(checker: &mut Checker, function: &Function, return_type: &Type) -> Type {
+    let (wrapper, kind, expected) = match function.generator {
+        true => (Type::Iterable, "generator", "an Iterable"),
+        false => (Type::Future, "async function", "a Future"),
+    };
+
+    match return_type {
+        Type::Generic(base, arguments) if **base == wrapper && arguments.len() == 1 => arguments[0].clone(),
+        Type::Dynamic => Type::Dynamic,
+        _ if *return_type == wrapper => Type::Dynamic,
+        _ => {
+            checker.error(format!(
+                "{} '{}' must return {}, not {}",
+                kind, function.name, expected, return_type
+            ));
+            Type::Dynamic
+        }
+    }
+}
+
+/// The type of the elements a for-in loop gets from a value of type `iterable`.
+///
+/// The builtin collections iterate natively, a class through the iterator
+/// protocol: `iterator()` returns an object whose `next()` returns the next
+/// element, or `nil` once there is none.
+fn iterated_type(checker: &mut Checker, iterable: &Type) -> Type {
+    match iterable {
+        Type::Dynamic | Type::List | Type::Set | Type::Map | Type::Iterable => Type::Dynamic,
+        Type::String => Type::String,
+        Type::Range => Type::Int,
+        Type::Generic(base, arguments) if matches!(**base, Type::List | Type::Set | Type::Map | Type::Iterable) => {
+            arguments.first().cloned().unwrap_or(Type::Dynamic)
+        }
+        Type::Class(_) | Type::Generic(..) => {
+            let iterator = match find_member(checker, iterable, "iterator") {
+                Some(Member::Method(signature)) if signature.required == 0 => signature.return_type,
+                _ => {
+                    checker.error(format!(
+                        "can't iterate over a value of type {}, it has no 'iterator()' method",
+                        iterable
+                    ));
+                    return Type::Dynamic;
+                }
+            };
+            if iterator == Type::Dynamic {
+                return Type::Dynamic;
+            }
+
+            match find_member(checker, &iterator, "next") {
+                Some(Member::Method(signature)) if signature.required == 0 => match signature.return_type {
+                    Type::Nullable(element) => *element,
+                    element => element,
+                },
+                _ => {
+                    checker.error(format!("iterator of type {} has no 'next()' method", iterator));
+                    Type::Dynamic
+                }
+            }
+        }
+        _ => {
+            checker.error(format!("can't iterate over a value of type {}", iterable));
+            Type::Dynamic
+        }
+    }
+}
+
+/// A parameter a call can leave out takes its default value, `nil` when it has none.
+fn check_default(checker: &mut Checker, parameter: &Parameter) {
+    let annotation = match &parameter.annotation {
+        Some(annotation) => annotation,
+        None => return,
+    };
+
+    match &parameter.default {
+        Some(default) => {
+            let actual = check_expression(checker, default);
+            if !is_assignable(checker, default, &actual, annotation) {
+                checker.error(format!(
+                    "can't use a value of type {} as the default of parameter '{}' of type {}",
+                    actual, parameter.name, annotation
+                ));
+            }
+        }
+        None if matches!(parameter.kind, ParameterKind::Optional | ParameterKind::Named)
+            && !Type::Null.is_assignable_to(annotation) =>
+        {
+            checker.error(format!(
+                "parameter '{}' of type {} needs a default value",
+                parameter.name, annotation
+            ));
+        }
+        None => {}
+    }
+}
+
+/// Checks the constants of a pattern and declares its bindings.
+fn check_pattern(checker: &mut Checker, pattern: &Pattern) {
+    match pattern {
+        Pattern::Binding(name) => checker.declare(name, Symbol::Variable(Type::Dynamic)),
+        Pattern::List(elements) => {
+            for element in elements {
+                check_pattern(checker, element);
+            }
+        }
+        Pattern::Map(entries) => {
+            for (_, value) in entries {
+                check_pattern(checker, value);
+            }
+        }
+        Pattern::Object(_, fields) => {
+            for (_, value) in fields {
+                check_pattern(checker, value);
+            }
+        }
+        Pattern::Constant(constant) => {
+            check_expression(checker, constant);
+        }
+        Pattern::Wildcard => {}
+    }
+}
+
+/// The enum and value a qualified constant pattern such as `Color.red` names.
+fn qualified_constant(pattern: &Pattern) -> Option<(&str, &str)> {
+    match pattern {
+        Pattern::Constant(Expression::Get(owner, name)) => match &**owner {
+            Expression::Variable(owner) => Some((owner, name)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The enum a switch value is a value of, and whether it may also be `nil`.
+fn switched_enum(checker: &Checker, scrutinee: &Type) -> Option<(String, bool)> {
+    let (name, nullable) = match scrutinee {
+        Type::Class(name) => (name, false),
+        Type::Nullable(inner) => match &**inner {
+            Type::Class(name) => (name, true),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    lookup_enum(checker, name)?;
+    Some((name.clone(), nullable))
+}
+
+/// Reports the cases naming a value of another enum than the one switched on.
+fn check_case_enums<'a>(checker: &mut Checker, scrutinee: &Type, cases: impl Iterator<Item = (&'a Pattern, bool)>) {
+    let name = match switched_enum(checker, scrutinee) {
+        Some((name, _)) => name,
+        None => return,
+    };
+
+    for (pattern, _) in cases {
+        if let Some((owner, value)) = qualified_constant(pattern) {
+            if owner != name && lookup_enum(checker, owner).is_some() {
+                checker.error(format!("case {}.{} can't match a value of type {}", owner, value, scrutinee));
+            }
+        }
+    }
+}
+
+/// Reports the values of an enum that no case of a switch matches. The enum is
+/// the type of the switch value, or the one every constant pattern names when
+/// the value is dynamic. A nullable value must also be matched by a `nil` case.
+fn check_exhaustive(checker: &mut Checker, scrutinee: &Type, cases: Vec<(&Pattern, bool)>) {
+    let (name, nullable) = match scrutinee {
+        Type::Class(_) | Type::Nullable(_) => match switched_enum(checker, scrutinee) {
+            Some(switched) => switched,
+            None => return,
+        },
+        Type::Dynamic => {
+            let mut owners = cases
+                .iter()
+                .filter(|(pattern, _)| matches!(pattern, Pattern::Constant(_)))
+                .map(|(pattern, _)| qualified_constant(pattern).map(|(owner, _)| owner));
+            match owners.next() {
+                Some(Some(owner)) if owners.all(|other| other == Some(owner)) => (owner.to_string(), false),
+                _ => return,
+            }
+        }
+        _ => return,
+    };
+
+    let mut missing: Vec<String> = match lookup_enum(checker, &name) {
+        Some(values) => values.iter().map(|value| format!("{}.{}", name, value)).collect(),
+        None => return,
+    };
+    if nullable {
+        missing.push("nil".to_string());
+    }
+
+    for (pattern, guarded) in cases {
+        // a guarded case may not match, so it covers nothing
+        if guarded {
+            continue;
+        }
+
+        match pattern {
+            Pattern::Wildcard | Pattern::Binding(_) => return,
+            Pattern::Constant(Expression::Nil) => missing.retain(|missing| missing != "nil"),
+            _ => {
+                if let Some((owner, value)) = qualified_constant(pattern) {
+                    if owner == name {
+                        missing.retain(|missing| *missing != format!("{}.{}", owner, value));
+                    }
+                }
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        let subject = if nullable { format!("{}?", name) } else { name };
+        checker.error(format!("switch on {} is not exhaustive, missing {}", subject, missing.join(", ")));
+    }
+}
+
+/// Integer literals are also accepted where a `double` is expected, as in `var ratio: double = 1;`.
+fn is_assignable(checker: &Checker, value: &Expression, actual: &Type, expected: &Type) -> bool {
+    is_subtype(checker, actual, expected)
+        || matches!(value, Expression::Integer(_)) && Type::Double.is_assignable_to(expected)
+}
+
+/// Like `Type::is_assignable_to`, also accepting an instance of a subclass where
+/// its superclass is expected.
+fn is_subtype(checker: &Checker, actual: &Type, expected: &Type) -> bool {
+    if actual.is_assignable_to(expected) {
+        return true;
+    }
+
+    match (actual, expected) {
+        (Type::Nullable(actual), Type::Nullable(expected)) => is_subtype(checker, actual, expected),
+        (Type::Nullable(_), _) => false,
+        (_, Type::Nullable(expected)) => is_subtype(checker, actual, expected),
+        _ => match superclass(checker, actual) {
+            Some(superclass) => is_subtype(checker, &superclass, expected),
+            None => false,
+        },
+    }
+}
+
+fn check_expression(checker: &mut Checker, expression: &Expression) -> Type {
+    match expression {
+        Expression::Number(_) => Type::Double,
+        Expression::Integer(_) => Type::Int,
+        Expression::String(_) => Type::String,
+        Expression::Boolean(_) => Type::Bool,
+        Expression::Nil => Type::Null,
+        Expression::List(elements) => {
+            let types: Vec<Type> = elements
+                .iter()
+                .map(|element| check_expression(checker, element))
+                .collect();
+
+            // the element type is only known when every element agrees on it
+            match types.split_first() {
+                Some((first, rest)) if rest.iter().all(|other| other == first) => {
+                    Type::Generic(Box::new(Type::List), vec![first.clone()])
+                }
+                _ => Type::List,
+            }
+        }
+        Expression::Record(positional, named) => {
+            let positional = positional
+                .iter()
+                .map(|field| check_expression(checker, field))
+                .collect();
+            let named = named
+                .iter()
+                .map(|(name, field)| (name.clone(), check_expression(checker, field)))
+                .collect();
+            Type::Record(positional, named)
+        }
+        Expression::Grouping(expression) => check_expression(checker, expression),
+        Expression::Variable(name) => match checker.lookup(name) {
+            Some(Symbol::Variable(declared)) => declared.clone(),
+            Some(Symbol::Function(_)) => Type::Function,
+            Some(Symbol::Class(_)) | Some(Symbol::Extension(..)) | Some(Symbol::Module(..)) | None => Type::Dynamic,
+        },
+        Expression::This => checker.classes.last().cloned().unwrap_or(Type::Dynamic),
+        Expression::Super => {
+            let this = checker.classes.last().cloned().unwrap_or(Type::Dynamic);
+            match superclass(checker, &this) {
+                Some(superclass) => superclass,
+                None if this == Type::Dynamic => Type::Dynamic,
+                None => {
+                    checker.error(format!("can't use 'super' in {}, it has no superclass", this));
+                    Type::Dynamic
+                }
+            }
+        }
+        Expression::Assign(name, value) => {
+            let actual = check_expression(checker, value);
+
+            match checker.lookup(name).cloned() {
+                Some(Symbol::Variable(declared)) if !is_assignable(checker, value, &actual, &declared) => {
+                    checker.error(format!(
+                        "can't assign a value of type {} to variable '{}' of type {}",
+                        actual, name, declared
+                    ));
+                }
+                Some(Symbol::Function(_)) => {
+                    checker.error(format!("can't assign to function '{}'", name));
+                }
+                Some(Symbol::Class(_)) => {
+                    checker.error(format!("can't assign to class '{}'", name));
+                }
+                _ => {}
+            }
+
+            actual
+        }
+        Expression::Binary(left, operator, right) => {
+            let left = check_expression(checker, left);
+            let right = check_expression(checker, right);
+            check_binary(checker, &left, operator, &right)
+        }
+        Expression::Unary(operator, right) => {
+            let right = check_expression(checker, right);
+            check_unary(checker, operator, &right)
+        }
+        Expression::Logical(left, operator, right) => {
+            let left = check_expression(checker, left);
+            let right = check_expression(checker, right);
+
+            match operator {
+                LogicalOperator::QuestionQuestion => match left {
+                    Type::Null => right,
+                    Type::Nullable(inner) if right.is_assignable_to(&inner) => *inner,
+                    _ if left == right => left,
+                    _ => Type::Dynamic,
+                },
+                LogicalOperator::And | LogicalOperator::Or if left == right => left,
+                LogicalOperator::And | LogicalOperator::Or => Type::Dynamic,
+            }
+        }
+        Expression::Call(callee, arguments, named) => check_call(checker, callee, arguments, named),
+        Expression::Get(object, name) => {
+            if let Expression::Variable(owner) = &**object {
+                if let Some(values) = lookup_enum(checker, owner) {
+                    return check_enum_value(checker, owner, &values, name);
+                }
+                if let Some(Symbol::Module(path, exports)) = checker.lookup(owner).cloned() {
+                    return check_export(checker, &path, &exports, name);
+                }
+            }
+            if let Some((class, statics)) = lookup_statics(checker, object) {
+                return match check_static(checker, class, &statics, name) {
+                    Some(_) => Type::Function,
+                    None => Type::Dynamic,
+                };
+            }
+
+            let object = check_expression(checker, object);
+            if let Type::Record(positional, named) = &object {
+                return check_record_field(checker, &object, positional, named, name);
+            }
+
+            match find_member(checker, &object, name) {
+                Some(Member::Field(declared)) => declared,
+                Some(Member::Method(_)) => Type::Function,
+                None => Type::Dynamic,
+            }
+        }
+        Expression::NullAwareGet(object, _) | Expression::NullAwareChain(object) => {
+            check_expression(checker, object);
+            Type::Dynamic
+        }
+        Expression::Index(object, index) => {
+            let object = check_expression(checker, object);
+            let index = check_expression(checker, index);
+
+            if let Some(result) = check_operator(checker, &object, "[]", &[index]) {
+                return result;
+            }
+
+            match object {
+                Type::Generic(base, arguments) if *base == Type::List => arguments[0].clone(),
+                _ => Type::Dynamic,
+            }
+        }
+        Expression::NullAwareIndex(object, index) => {
+            check_expression(checker, object);
+            check_expression(checker, index);
+            Type::Dynamic
+        }
+        Expression::Set(object, name, value) => {
+            let object = check_expression(checker, object);
+            let actual = check_expression(checker, value);
+
+            if let Type::Record(_, _) = object {
+                checker.error(format!("can't assign to field '{}' of a record", name));
+            }
+
+            if let Some(Member::Field(declared)) = find_member(checker, &object, name) {
+                if !is_assignable(checker, value, &actual, &declared) {
+                    checker.error(format!(
+                        "can't assign a value of type {} to field '{}' of type {}",
+                        actual, name, declared
+                    ));
+                }
+            }
+
+            actual
+        }
+        Expression::SetIndex(object, index, value) => {
+            let object = check_expression(checker, object);
+            let index = check_expression(checker, index);
+            let value = check_expression(checker, value);
+
+            check_operator(checker, &object, "[]=", &[index, value.clone()]);
+            value
+        }
+        Expression::CompoundAssign(target, operator, value) => {
+            let declared = check_expression(checker, target);
+            let actual = check_expression(checker, value);
+
+            let result = match compound_operator(operator) {
+                Some(operator) => check_binary(checker, &declared, &operator, &actual),
+                None => match &declared {
+                    Type::Nullable(inner) if actual.is_assignable_to(inner) => declared.clone(),
+                    _ => actual,
+                },
+            };
+
+            if !result.is_assignable_to(&declared) {
+                checker.error(format!(
+                    "can't assign a value of type {} with '{}' to a target of type {}",
+                    result, operator, declared
+                ));
+            }
+
+            declared
+        }
+        Expression::Postfix(target, operator) => {
+            let declared = check_expression(checker, target);
+
+            let binary = match operator {
+                PostfixOperator::PlusPlus => BinaryOperator::Plus,
+                PostfixOperator::MinusMinus => BinaryOperator::Minus,
+            };
+            let result = check_binary(checker, &declared, &binary, &Type::Int);
+            if !result.is_assignable_to(&declared) {
+                checker.error(format!(
+                    "can't assign a value of type {} with '{}' to a target of type {}",
+                    result, operator, declared
+                ));
+            }
+
+            // the update evaluates to the value before it
+            declared
+        }
+        Expression::Destructure(_, value) => check_expression(checker, value),
+        Expression::Cascade(receiver, sections) => {
+            let receiver = check_expression(checker, receiver);
+            for section in sections {
+                check_expression(checker, section);
+            }
+            receiver
+        }
+        Expression::CascadeReceiver => Type::Dynamic,
+        Expression::TypeCheck(value, operator, target) => {
+            check_expression(checker, value);
+            check_annotation(checker, target);
+
+            match operator {
+                TypeOperator::Is | TypeOperator::IsNot => Type::Bool,
+                TypeOperator::As => target.clone(),
+            }
+        }
+        Expression::Ternary(condition, then_branch, else_branch) => {
+            check_expression(checker, condition);
+            let then_type = check_expression(checker, then_branch);
+            let else_type = check_expression(checker, else_branch);
+
+            if then_type == else_type {
+                then_type
+            } else {
+                Type::Dynamic
+            }
+        }
+        Expression::Range(start, _, end) => {
+            for bound in [start, end] {
+                let bound = check_expression(checker, bound);
+                if !bound.is_assignable_to(&Type::Int) {
+                    checker.error(format!("range bounds must be int, found {}", bound));
+                }
+            }
+            Type::Range
+        }
+        Expression::Spread(value) => {
+            let list = check_expression(checker, value);
+            if !list.is_assignable_to(&Type::List) {
+                checker.error(format!("can't spread a value of type {}", list));
+            }
+            // the elements are passed one by one, so the spread itself has no type
+            Type::Dynamic
+        }
+        Expression::Await(value) => match check_expression(checker, value) {
+            Type::Generic(base, arguments) if *base == Type::Future && arguments.len() == 1 => arguments[0].clone(),
+            Type::Future => Type::Dynamic,
+            value => value,
+        },
+        Expression::Throw(value) => {
+            check_expression(checker, value);
+            // a throw never produces a value, so it fits wherever one is expected
+            Type::Dynamic
+        }
+        Expression::Switch(value, arms) => {
+            let scrutinee = check_expression(checker, value);
+            let cases = arms.iter().map(|arm| (&arm.pattern, arm.guard.is_some()));
+            check_case_enums(checker, &scrutinee, cases.clone());
+            check_exhaustive(checker, &scrutinee, cases.collect());
+
+            let mut result: Option<Type> = None;
+            for arm in arms {
+                checker.scopes.push(HashMap::new());
+                check_pattern(checker, &arm.pattern);
+                if let Some(guard) = &arm.guard {
+                    check_expression(checker, guard);
+                }
+                let body = check_expression(checker, &arm.body);
+                checker.scopes.pop();
+
+                result = match result {
+                    Some(previous) if previous != body => Some(Type::Dynamic),
+                    _ => Some(body),
+                };
+            }
+
+            result.unwrap_or(Type::Dynamic)
+        }
+    }
+}
+
+fn check_call(
+    checker: &mut Checker,
+    callee: &Expression,
+    arguments: &[Expression],
+    named: &[(String, Expression)],
+) -> Type {
+    let signature = match callee {
+        Expression::Variable(name) => match checker.lookup(name) {
+            Some(Symbol::Function(signature)) => Some((name.clone(), signature.clone())),
+            Some(Symbol::Class(info)) if !info.values.is_empty() => {
+                checker.error(format!("can't create an instance of enum '{}'", name));
+                None
+            }
+            Some(Symbol::Class(info)) => Some((name.clone(), constructor(checker, name, info))),
+            _ => None,
+        },
+        Expression::Get(object, _) if is_module(checker, object) => {
+            check_expression(checker, callee);
+            None
+        }
+        Expression::Get(object, name) => match lookup_statics(checker, object) {
+            Some((class, statics)) => {
+                check_static(checker, class, &statics, name).map(|signature| (name.clone(), signature))
+            }
+            None => {
+                let object = check_expression(checker, object);
+                match find_member(checker, &object, name) {
+                    Some(Member::Method(signature)) => Some((name.clone(), signature)),
+                    _ => None,
+                }
+            }
+        },
+        _ => {
+            check_expression(checker, callee);
+            None
+        }
+    };
+
+    match signature {
+        Some((name, signature)) => check_arguments(checker, &name, &signature, arguments, named),
+        None => {
+            for argument in arguments.iter().chain(named.iter().map(|(_, argument)| argument)) {
+                check_expression(checker, argument);
+            }
+            Type::Dynamic
+        }
+    }
+}
+
+/// Checks the arguments of a call against the signature of the callee and
+/// returns the type of the call.
+fn check_arguments(
+    checker: &mut Checker,
+    name: &str,
+    signature: &Signature,
+    arguments: &[Expression],
+    named: &[(String, Expression)],
+) -> Type {
+    let actuals: Vec<Type> = arguments
+        .iter()
+        .map(|argument| check_expression(checker, argument))
+        .collect();
+    let named_actuals: Vec<Type> = named
+        .iter()
+        .map(|(_, argument)| check_expression(checker, argument))
+        .collect();
+
+    // a spread passes any number of arguments, so only those before it have a known position
+    let spread = arguments
+        .iter()
+        .position(|argument| matches!(argument, Expression::Spread(_)));
+    let count = arguments
+        .iter()
+        .filter(|argument| !matches!(argument, Expression::Spread(_)))
+        .count();
+
+    let positional = signature.parameters.len();
+    let too_few = spread.is_none() && count < signature.required;
+    let too_many = signature.rest.is_none() && count > positional;
+    if too_few || too_many {
+        let expected = match (&signature.rest, signature.required == positional) {
+            (Some(_), _) => format!("at least {}", signature.required),
+            (None, true) => positional.to_string(),
+            (None, false) => format!("{} to {}", signature.required, positional),
+        };
+        checker.error(format!(
+            "function '{}' expects {} arguments but got {}",
+            name, expected, count
+        ));
+        // the type parameters are left uninferred, they are `dynamic`
+        return signature.return_type.substitute(&infer(signature, std::iter::empty()));
+    }
+
+    // every argument with the parameter it is passed for
+    let mut passed: Vec<(&Expression, &Type, &str, &Type)> = Vec::new();
+    let known = arguments.iter().zip(&actuals).take(spread.unwrap_or(arguments.len()));
+    for (index, (argument, actual)) in known.enumerate() {
+        if let Some((parameter, expected)) = signature.parameters.get(index).or(signature.rest.as_ref()) {
+            passed.push((argument, actual, parameter, expected));
+        }
+    }
+
+    for ((parameter, argument), actual) in named.iter().zip(&named_actuals) {
+        match signature.named.iter().find(|(other, _, _)| other == parameter) {
+            Some((_, expected, _)) => passed.push((argument, actual, parameter, expected)),
+            None => checker.error(format!("function '{}' has no parameter named '{}'", name, parameter)),
+        }
+    }
+
+    for (parameter, _, required) in &signature.named {
+        if *required && !named.iter().any(|(other, _)| other == parameter) {
+            checker.error(format!("function '{}' is missing required argument '{}'", name, parameter));
+        }
+    }
+
+    let bindings = infer(signature, passed.iter().map(|(_, actual, _, expected)| (*expected, *actual)));
+    for (argument, actual, parameter, expected) in passed {
+        let expected = expected.substitute(&bindings);
+        if !is_assignable(checker, argument, actual, &expected) {
+            checker.error(format!(
+                "can't pass a value of type {} to parameter '{}' of '{}' of type {}",
+                actual, parameter, name, expected
+            ));
+        }
+    }
+
+    signature.return_type.substitute(&bindings)
+}
+
+/// `$1`, `$2`... read the positional fields of a record, in order.
+fn check_record_field(
+    checker: &mut Checker,
+    record: &Type,
+    positional: &[Type],
+    named: &[(String, Type)],
+    name: &str,
+) -> Type {
+    let field = match name.strip_prefix('$').and_then(|index| index.parse::<usize>().ok()) {
+        Some(index) if index >= 1 => positional.get(index - 1),
+        _ => named.iter().find(|(field, _)| field == name).map(|(_, field)| field),
+    };
+
+    match field {
+        Some(field) => field.clone(),
+        None => {
+            checker.error(format!("record {} has no field '{}'", record, name));
+            Type::Dynamic
+        }
+    }
+}
+
+/// The signature of a static method of a class, reporting a name the class
+/// doesn't declare.
+fn check_static(
+    checker: &mut Checker,
+    class: &str,
+    statics: &HashMap<String, Signature>,
+    name: &str,
+) -> Option<Signature> {
+    let signature = statics.get(name).cloned();
+    if signature.is_none() {
+        checker.error(format!("class '{}' has no static method '{}'", class, name));
+    }
+
+    signature
+}
+
+fn check_enum_value(checker: &mut Checker, name: &str, values: &[String], member: &str) -> Type {
+    if member == "values" {
+        return Type::Generic(Box::new(Type::List), vec![Type::Class(name.to_string())]);
+    }
+
+    if !values.iter().any(|value| value == member) {
+        checker.error(format!("enum '{}' has no value '{}'", name, member));
+        return Type::Dynamic;
+    }
+
+    Type::Class(name.to_string())
+}
+
+fn is_module(checker: &Checker, object: &Expression) -> bool {
+    match object {
+        Expression::Variable(name) => matches!(checker.lookup(name), Some(Symbol::Module(..))),
+        _ => false,
+    }
+}
+
+fn check_export(checker: &mut Checker, path: &str, exports: &[String], name: &str) -> Type {
+    if !exports.iter().any(|export| export == name) {
+        checker.error(format!("module '{}' doesn't export '{}'", path, name));
+    }
+
+    Type::Dynamic
+}
+
+/// Calling a class runs its `init` method, possibly inherited, and returns an
+/// instance whose type arguments are inferred like those of a generic function.
+fn constructor(checker: &Checker, name: &str, info: &ClassInfo) -> Signature {
+    let instance = class_type(name, &info.type_parameters);
+
+    let mut signature = match find_member(checker, &instance, "init") {
+        Some(Member::Method(init)) => init,
+        _ => Signature {
+            type_parameters: Vec::new(),
+            parameters: Vec::new(),
+            required: 0,
+            named: Vec::new(),
+            rest: None,
+            return_type: Type::Dynamic,
+        },
+    };
+    signature.type_parameters.extend(info.type_parameters.iter().cloned());
+    signature.return_type = instance;
+    signature
+}
+
+/// Binds the type parameters of a generic signature to the types of the
+/// arguments passed for them, those appearing in no argument are `dynamic`.
+fn infer<'a>(signature: &Signature, passed: impl Iterator<Item = (&'a Type, &'a Type)>) -> HashMap<String, Type> {
+    let mut bindings = HashMap::new();
+    for (expected, actual) in passed {
+        infer_type(&signature.type_parameters, expected, actual, &mut bindings);
+    }
+
+    for parameter in &signature.type_parameters {
+        bindings.entry(parameter.clone()).or_insert(Type::Dynamic);
+    }
+    bindings
+}
+
+fn infer_type(type_parameters: &[String], expected: &Type, actual: &Type, bindings: &mut HashMap<String, Type>) {
+    match (expected, actual) {
+        (Type::Parameter(name), _) if type_parameters.contains(name) => {
+            // two arguments of different types widen the binding to a common type when there is one
+            let inferred = match bindings.get(name) {
+                None => actual.clone(),
+                Some(bound) if actual.is_assignable_to(bound) => bound.clone(),
+                Some(bound) if bound.is_assignable_to(actual) => actual.clone(),
+                Some(bound) if is_numeric(bound) && is_numeric(actual) => Type::Num,
+                Some(bound) => bound.clone(),
+            };
+            bindings.insert(name.clone(), inferred);
+        }
+        (Type::Nullable(expected), Type::Nullable(actual)) => infer_type(type_parameters, expected, actual, bindings),
+        (Type::Nullable(_), Type::Null) => {}
+        (Type::Nullable(expected), _) => infer_type(type_parameters, expected, actual, bindings),
+        (Type::Generic(base, expected), Type::Generic(other, actual)) if base == other => {
+            for (expected, actual) in expected.iter().zip(actual) {
+                infer_type(type_parameters, expected, actual, bindings);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The binary operator a compound assignment combines with, `??=` has none
+fn compound_operator(operator: &AssignOperator) -> Option<BinaryOperator> {
+    match operator {
+        AssignOperator::Plus => Some(BinaryOperator::Plus),
+        AssignOperator::Minus => Some(BinaryOperator::Minus),
+        AssignOperator::Star => Some(BinaryOperator::Star),
+        AssignOperator::Slash => Some(BinaryOperator::Slash),
+        AssignOperator::Percent => Some(BinaryOperator::Percent),
+        AssignOperator::QuestionQuestion => None,
+    }
+}
+
+fn is_numeric(value: &Type) -> bool {
+    matches!(value, Type::Int | Type::Double | Type::Num)
+}
+
+fn check_binary(checker: &mut Checker, left: &Type, operator: &BinaryOperator, right: &Type) -> Type {
+    // `a != b` calls the `==` operator of `a` and negates its result
+    let symbol = match operator {
+        BinaryOperator::BangEqual => "==".to_string(),
+        operator => operator.to_string(),
+    };
+    if let Some(result) = check_operator(checker, left, &symbol, std::slice::from_ref(right)) {
+        return match operator {
+            BinaryOperator::EqualEqual | BinaryOperator::BangEqual => Type::Bool,
+            _ => result,
+        };
+    }
+
+    let result = match operator {
+        BinaryOperator::EqualEqual | BinaryOperator::BangEqual => Some(Type::Bool),
+        _ if *left == Type::Dynamic || *right == Type::Dynamic => match operator {
+            BinaryOperator::Greater
+            | BinaryOperator::GreaterEqual
+            | BinaryOperator::Less
+            | BinaryOperator::LessEqual => Some(Type::Bool),
+            _ => Some(Type::Dynamic),
+        },
+        BinaryOperator::Plus if *left == Type::String && *right == Type::String => Some(Type::String),
+        BinaryOperator::Plus | BinaryOperator::Minus | BinaryOperator::Star | BinaryOperator::Percent
+            if is_numeric(left) && is_numeric(right) =>
+        {
+            match (left, right) {
+                (Type::Int, Type::Int) => Some(Type::Int),
+                (Type::Double, _) | (_, Type::Double) => Some(Type::Double),
+                _ => Some(Type::Num),
+            }
+        }
+        BinaryOperator::Slash if is_numeric(left) && is_numeric(right) => Some(Type::Double),
+        BinaryOperator::StarStar if is_numeric(left) && is_numeric(right) => Some(Type::Num),
+        BinaryOperator::Greater
+        | BinaryOperator::GreaterEqual
+        | BinaryOperator::Less
+        | BinaryOperator::LessEqual
+            if is_numeric(left) && is_numeric(right) =>
+        {
+            Some(Type::Bool)
+        }
+        BinaryOperator::Ampersand
+        | BinaryOperator::Pipe
+        | BinaryOperator::Caret
+        | BinaryOperator::LessLess
+        | BinaryOperator::GreaterGreater
+        | BinaryOperator::GreaterGreaterGreater
+            if *left == Type::Int && *right == Type::Int =>
+        {
+            Some(Type::Int)
+        }
+        _ => None,
+    };
+
+    match result {
+        Some(result) => result,
+        None => {
+            checker.error(format!(
+                "operator '{}' can't be applied to {} and {}",
+                operator, left, right
+            ));
+            Type::Dynamic
+        }
+    }
+}
+
+fn check_unary(checker: &mut Checker, operator: &UnaryOperator, right: &Type) -> Type {
+    let symbol = match operator {
+        UnaryOperator::Minus => "unary-".to_string(),
+        operator => operator.to_string(),
+    };
+    if let Some(result) = check_operator(checker, right, &symbol, &[]) {
+        return result;
+    }
+
+    match (operator, right) {
+        (UnaryOperator::Bang, _) => Type::Bool,
+        (_, Type::Dynamic) => Type::Dynamic,
+        (UnaryOperator::Minus, right) if is_numeric(right) => right.clone(),
+        (UnaryOperator::Tilde, Type::Int) => Type::Int,
+        _ => {
+            checker.error(format!("operator '{}' can't be applied to {}", operator, right));
+            Type::Dynamic
+        }
+    }
+}
+
+/// The result type of an operator declared by the class of `receiver`, checking
+/// the operands against its parameters, `None` when the class declares none.
+fn check_operator(checker: &mut Checker, receiver: &Type, operator: &str, operands: &[Type]) -> Option<Type> {
+    let signature = match find_member(checker, receiver, operator) {
+        Some(Member::Method(signature)) => signature,
+        _ => return None,
+    };
+
+    for ((parameter, expected), actual) in signature.parameters.iter().zip(operands) {
+        if !is_subtype(checker, actual, expected) {
+            checker.error(format!(
+                "can't pass a value of type {} to parameter '{}' of operator '{}' of type {}",
+                actual, parameter, operator, expected
+            ));
+        }
+    }
+
+    Some(signature.return_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_program, Checker};
+    use crate::{
+        diagnostic::Diagnostic,
+        parser::{parse_program, Parser},
+        scanner::Scanner,
+        statement::Statement,
+    };
+
+    fn check_source(source: &str) -> Vec<Diagnostic> {
+        let mut scanner = Scanner::new(source.to_string());
+        let mut parser = Parser::new(scanner.scan().clone());
+        let statements = parse_program(&mut parser);
+        assert_eq!(parser.diagnostics, vec![]);
+
+        let mut checker = Checker::new();
+        check_program(&mut checker, &statements);
+        checker.diagnostics
+    }
+
+    #[test]
+    fn test_unannotated_code_is_dynamic() {
+        let diagnostics = check_source("var count = 0;\ncount = \"many\";\nprint count - 1;");
+
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn test_variable_mismatch() {
+        let diagnostics = check_source(
+            "var count: int = 0;\nvar ratio: double = 1;\nvar name: String? = nil;\ncount = \"many\";\nvar total: int = count * 2.5;\nvar x: int = 1.0;",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(4, "can't assign a value of type String to variable 'count' of type int".to_string()),
+                Diagnostic::new(5, "can't assign a value of type double to variable 'total' of type int".to_string()),
+                Diagnostic::new(6, "can't assign a value of type double to variable 'x' of type int".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_signature() {
+        let diagnostics = check_source(
+            "print add(1, \"2\");\nadd(1);\nvar sum: String = add(1, 2);\nfunc add(a: int, b: int): int {\n  return a + b;\n}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "can't pass a value of type String to parameter 'b' of 'add' of type int".to_string()),
+                Diagnostic::new(2, "function 'add' expects 2 arguments but got 1".to_string()),
+                Diagnostic::new(3, "can't assign a value of type int to variable 'sum' of type String".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_return_mismatch() {
+        let diagnostics = check_source("func name(): String {\n  return 1;\n}\nfunc nothing() {\n  return 1;\n}");
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(
+                2,
+                "can't return a value of type int from function 'name' with return type String".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_operator_mismatch() {
+        let diagnostics = check_source("func f(a: int, b: String) {\n  print a - b;\n  print -b;\n  print a + 1 < 2;\n}");
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(2, "operator '-' can't be applied to int and String".to_string()),
+                Diagnostic::new(3, "operator '-' can't be applied to String".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generic_function_inference() {
+        let diagnostics = check_source(
+            "func first<T>(xs: List<T>): T {\n  return xs[0];\n}\nvar name: String = first([\"a\", \"b\"]);\nvar count: int = first([\"a\"]);\nvar any: int = first([1, \"a\"]);",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(5, "can't assign a value of type String to variable 'count' of type int".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_generic_class() {
+        let diagnostics = check_source(
+            "class Box<T> {\n  var value: T;\n  init(value: T) {\n    this.value = value;\n  }\n  get(): T {\n    return 1;\n  }\n}\nclass Labels extends Box<String> {}\nvar box: Box<int> = Box(1);\nvar doubled: int = box.get() * 2;\nbox.value = \"one\";\nvar labels: Box<String> = Labels(2);",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(7, "can't return a value of type int from function 'get' with return type T".to_string()),
+                Diagnostic::new(13, "can't assign a value of type String to field 'value' of type int".to_string()),
+                Diagnostic::new(14, "can't pass a value of type int to parameter 'value' of 'Labels' of type String".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_types() {
+        let diagnostics = check_source(
+            "var a: Foo;\nfunc f(b: List<Bar?>): (int, Baz)? { return nil; }\nclass C { var d: C; }\nprint a is Qux;\nfunc g<T>(t: T, p: Point): T { return t; }\nclass Point {}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "unknown type 'Foo'".to_string()),
+                Diagnostic::new(2, "unknown type 'Bar'".to_string()),
+                Diagnostic::new(2, "unknown type 'Baz'".to_string()),
+                Diagnostic::new(4, "unknown type 'Qux'".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_uninferred_type_parameters() {
+        let diagnostics = check_source("class Box<T> {\n  var value: T;\n}\nvar box: Box<int> = Box(1);");
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(4, "function 'Box' expects 0 arguments but got 1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_super() {
+        let diagnostics = check_source(
+            "class Shape {\n  var name: String;\n  area(): double { return 0.0; }\n}\nclass Square extends Shape {\n  area(): double {\n    var name: int = super.name;\n    return super.area(1);\n  }\n}\nclass Point {\n  describe() { return super.describe(); }\n}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(7, "can't assign a value of type String to variable 'name' of type int".to_string()),
+                Diagnostic::new(8, "function 'area' expects 0 arguments but got 1".to_string()),
+                Diagnostic::new(12, "can't use 'super' in Point, it has no superclass".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inheritance_cycle() {
+        let diagnostics = check_source("class A extends B {}\nclass B extends A {}\nprint A().missing;");
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "class 'A' can't inherit from itself".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_values() {
+        let diagnostics = check_source(
+            "enum Planet {\n  earth(9.8),\n  mars(\"3.7\");\n  var gravity: double;\n  init(gravity: double) {\n    this.gravity = gravity;\n  }\n}\nvar index: int = Planet.earth.index;\nvar planets: List<Planet> = Planet.values;\nprint Planet.venus;\nPlanet(1.0);",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(3, "can't pass a value of type String to parameter 'gravity' of 'Planet' of type double".to_string()),
+                Diagnostic::new(11, "enum 'Planet' has no value 'venus'".to_string()),
+                Diagnostic::new(12, "can't create an instance of enum 'Planet'".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exhaustive_switch() {
+        let diagnostics = check_source(
+            "enum Color { red, green, blue }\nfunc name(color: Color): String {\n  switch (color) {\n    case Color.red: return \"red\";\n    case Color.green when true: return \"green\";\n  }\n  return switch (color) { Color.red => \"red\", _ => \"other\" };\n}\nswitch (c) { case Color.blue: print 1; }\nswitch (c) { case Color.blue: print 1; default: print 0; }",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(3, "switch on Color is not exhaustive, missing Color.green, Color.blue".to_string()),
+                Diagnostic::new(9, "switch on Color is not exhaustive, missing Color.red, Color.green".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_switch_on_nullable_and_other_enums() {
+        let diagnostics = check_source(
+            "enum E { a, b }\nenum F { a }\nfunc f(e: E?, g: E) {\n  switch (e) { case E.a: print 1; case E.b: print 2; }\n  switch (e) { case E.a: print 1; case E.b: print 2; case nil: print 3; }\n  print switch (e) { E.a => 1, _ => 2 };\n  switch (g) { case E.a: print 1; case F.a: print 2; default: print 3; }\n}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(4, "switch on E? is not exhaustive, missing nil".to_string()),
+                Diagnostic::new(7, "case F.a can't match a value of type E".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_records() {
+        let diagnostics = check_source(
+            "var pair: (int, String) = (1, \"one\");\nvar point: (x: int, y: int) = (y: 2, x: 1);\nvar count: int = pair.$2;\nprint pair.$3;\npoint.x = 3;\nvar wrong: (int, int) = (1, \"one\");",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(3, "can't assign a value of type String to variable 'count' of type int".to_string()),
+                Diagnostic::new(4, "record (int, String) has no field '$3'".to_string()),
+                Diagnostic::new(5, "can't assign to field 'x' of a record".to_string()),
+                Diagnostic::new(6, "can't assign a value of type (int, String) to variable 'wrong' of type (int, int)".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_named_and_optional_parameters() {
+        let diagnostics = check_source(
+            "func greet(name: String, {greeting = \"Hi\", required times: int}) {}\ngreet(\"Ada\", times: 2);\ngreet(\"Ada\", greeting: \"Hey\", times: 1);\ngreet(times: 2);\ngreet(\"Ada\", loud: true);\nfunc pad(text: String, [width: int = \"8\", fill: String]) {}\npad(\"x\", 2, \"-\", 4);\ngreet(\"Ada\", times: \"twice\");",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(4, "function 'greet' expects 1 arguments but got 0".to_string()),
+                Diagnostic::new(5, "function 'greet' has no parameter named 'loud'".to_string()),
+                Diagnostic::new(5, "function 'greet' is missing required argument 'times'".to_string()),
+                Diagnostic::new(6, "can't use a value of type String as the default of parameter 'width' of type int".to_string()),
+                Diagnostic::new(6, "parameter 'fill' of type String needs a default value".to_string()),
+                Diagnostic::new(7, "function 'pad' expects 1 to 3 arguments but got 4".to_string()),
+                Diagnostic::new(8, "can't pass a value of type String to parameter 'times' of 'greet' of type int".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rest_parameters() {
+        let diagnostics = check_source(
+            "func sum(first: int, ...rest: int): int {\n  var all: List<int> = rest;\n  return first;\n}\nsum(1, 2, 3);\nsum();\nsum(1, \"two\");\nsum(...[1, 2]);\nfunc pair(a, b) {}\npair(1, ...[2], 3, 4);\nsum(...5);",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(6, "function 'sum' expects at least 1 arguments but got 0".to_string()),
+                Diagnostic::new(7, "can't pass a value of type String to parameter 'rest' of 'sum' of type int".to_string()),
+                Diagnostic::new(10, "function 'pair' expects 2 arguments but got 3".to_string()),
+                Diagnostic::new(11, "can't spread a value of type int".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generators() {
+        let diagnostics = check_source(
+            "func* count(n: int): Iterable<int> {\n  yield n;\n  yield \"done\";\n}\nvar numbers: Iterable<int> = count(3);\nvar words: Iterable<String> = count(3);\nvar list: Iterable<int> = [1, 2];\nvar range: Iterable<int> = 0..3;\nfunc* broken(): int {}\nfunc* raw(): Iterable {}\nvar xs: List<int> = raw();\nvar all: Iterable = count(3);\nvar some: Iterable<int> = raw();",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(3, "can't yield a value of type String from generator 'count' yielding int".to_string()),
+                Diagnostic::new(6, "can't assign a value of type Iterable<int> to variable 'words' of type Iterable<String>".to_string()),
+                Diagnostic::new(9, "generator 'broken' must return an Iterable, not int".to_string()),
+                Diagnostic::new(11, "can't assign a value of type Iterable to variable 'xs' of type List<int>".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_async_functions() {
+        let diagnostics = check_source(
+            "async func answer(): Future<int> {\n  return 42;\n}\nasync func main() {\n  var value: int = await answer();\n  var text: String = await answer();\n  var pending: Future<int> = answer();\n}\nasync func wrong(): Future<int> {\n  return \"42\";\n}\nasync func broken(): int {}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(6, "can't assign a value of type int to variable 'text' of type String".to_string()),
+                Diagnostic::new(10, "can't return a value of type String from function 'wrong' with return type int".to_string()),
+                Diagnostic::new(12, "async function 'broken' must return a Future, not int".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fibers() {
+        let diagnostics = check_source(
+            "func walk() {\n  Fiber.yield(1);\n}\nvar fiber: Fiber = Fiber(walk);\nvar step = fiber.resume();\nvar done: bool = fiber.isDone;\nvar status: int = fiber.status;\nfiber.resume(1, 2);\nFiber(1);\nFiber.yeild(1);\nFiber.yield(1, 2);\nvar pause = Fiber.yield;",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(7, "can't assign a value of type String to variable 'status' of type int".to_string()),
+                Diagnostic::new(8, "function 'resume' expects 0 to 1 arguments but got 2".to_string()),
+                Diagnostic::new(9, "can't pass a value of type int to parameter 'body' of 'Fiber' of type Function".to_string()),
+                Diagnostic::new(10, "class 'Fiber' has no static method 'yeild'".to_string()),
+                Diagnostic::new(11, "function 'yield' expects 0 to 1 arguments but got 2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        let diagnostics = check_source(
+            "class Vector {\n  var x: double;\n  operator +(other: Vector): Vector {}\n  operator -(): Vector {}\n  operator ==(other): bool {}\n  operator [](i: int): double {}\n}\nvar a: Vector = Vector();\nvar sum: Vector = a + a;\nvar negated: Vector = -a;\nvar same: bool = a != a;\nvar x: double = a[0];\nvar wrong: int = a + a;\na + 1;\na * a;",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(13, "can't assign a value of type Vector to variable 'wrong' of type int".to_string()),
+                Diagnostic::new(14, "can't pass a value of type int to parameter 'other' of operator '+' of type Vector".to_string()),
+                Diagnostic::new(15, "operator '*' can't be applied to Vector and Vector".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_updates() {
+        let diagnostics = check_source(
+            "var s: String = \"a\";\ns++;\n++s;\ns += 1;\nvar n: int = 0;\nvar before: int = n--;\nvar ratio: double = 0.5;\nratio++;",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(2, "operator '+' can't be applied to String and int".to_string()),
+                Diagnostic::new(3, "operator '+' can't be applied to String and int".to_string()),
+                Diagnostic::new(4, "operator '+' can't be applied to String and int".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subclass_operands() {
+        let diagnostics = check_source(
+            "class Vec {\n  operator +(other: Vec): Vec {}\n  add(other: Vec): Vec {}\n}\nclass Vec3 extends Vec {}\nvar a: Vec = Vec();\nvar b: Vec3 = Vec3();\nvar sum: Vec = a + b;\nsum = a.add(b);\nsum = b + b;\nsum = a + \"b\";",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(11, "can't pass a value of type String to parameter 'other' of operator '+' of type Vec".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_for_in() {
+        let diagnostics = check_source(
+            "class Countdown {\n  iterator(): Counter {}\n}\nclass Counter {\n  next(): int? {}\n}\nclass Broken {\n  iterator(): Countdown {}\n}\nclass Plain {}\nfor (var n in Countdown()) { var s: String = n; }\nfor (var c in \"abc\") { var i: int = c; }\nfor (var i in 0..3) { var ok: int = i; }\nfor (var x in Broken()) {}\nfor (var x in Plain()) {}\nfor (var x in 42) {}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(11, "can't assign a value of type int to variable 's' of type String".to_string()),
+                Diagnostic::new(12, "can't assign a value of type String to variable 'i' of type int".to_string()),
+                Diagnostic::new(14, "iterator of type Countdown has no 'next()' method".to_string()),
+                Diagnostic::new(15, "can't iterate over a value of type Plain, it has no 'iterator()' method".to_string()),
+                Diagnostic::new(16, "can't iterate over a value of type int".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extensions() {
+        let diagnostics = check_source(
+            "class Animal {\n  name(): String {}\n}\nclass Dog extends Animal {}\nextension Loud on String {\n  shout(times: int): String {\n    var self: int = this;\n  }\n}\nextension Named on Animal {\n  name(): int {}\n  greet(): String {}\n}\nvar a: int = \"hi\".shout(2);\n\"hi\".shout(\"twice\");\nvar b: String = Dog().greet();\nvar c: String = Dog().name();\n{\n  extension Count on int {\n    double(): int {}\n  }\n}\nvar d: String = 1.double();",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(7, "can't assign a value of type String to variable 'self' of type int".to_string()),
+                Diagnostic::new(14, "can't assign a value of type String to variable 'a' of type int".to_string()),
+                Diagnostic::new(15, "can't pass a value of type String to parameter 'times' of 'shout' of type int".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_imports() {
+        let mut scanner = Scanner::new(
+            "import \"math.ql\" as math;\nimport \"util.ql\";\nprint math.square(2) + math.tau;\nvar s: String = trim(\" a \");\nexport func f(): int { return math.pi * math.e; }"
+                .to_string(),
+        );
+        let mut parser = Parser::new(scanner.scan().clone());
+        let mut statements = parse_program(&mut parser);
+
+        // as the module loader leaves them
+        for (statement, exports) in statements.iter_mut().zip([vec!["square", "pi"], vec!["trim"]]) {
+            if let Statement::Import(import) = statement {
+                import.exports = Some(exports.into_iter().map(String::from).collect());
+            }
+        }
+
+        let mut checker = Checker::new();
+        check_program(&mut checker, &statements);
+
+        assert_eq!(
+            checker.diagnostics,
+            vec![
+                Diagnostic::new(3, "module 'math.ql' doesn't export 'tau'".to_string()),
+                Diagnostic::new(5, "module 'math.ql' doesn't export 'e'".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,19 @@
+use core::fmt;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Diagnostic {
+    pub line: i32,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(line: i32, message: String) -> Diagnostic {
+        Diagnostic { line, message }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[line {}] Error: {}", self.line, self.message)
+    }
+}
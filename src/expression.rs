@@ -1,6 +1,8 @@
 
 use core::fmt;
 
+use crate::{pattern::Pattern, types::Type};
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum Expression {
     Binary(Box<Expression>, BinaryOperator, Box<Expression>),
@@ -8,21 +10,111 @@ pub enum Expression {
 
     String(String),
     Boolean(bool),
+    /// A double, `2.5`
     Number(f64),
+    /// An int, `2`
+    Integer(i64),
+    Nil,
+    List(Vec<Expression>),
+    /// `(1, "a")`, `(x: 1, y: 2)` or `(1,)`, the positional fields followed by
+    /// the named ones. Positional fields are read as `$1`, `$2`... and named
+    /// ones by their name. Records can't be modified and two records are equal
+    /// when they have the same fields with equal values.
+    Record(Vec<Expression>, Vec<(String, Expression)>),
 
     Unary(UnaryOperator, Box<Expression>),
     Variable(String),
+    /// The instance a method was called on
+    This,
+    /// The instance a method was called on, whose members are looked up from
+    /// the superclass, as in `super.init()`
+    Super,
     Assign(String, Box<Expression>),
+    /// `callee(positional, name: value)`
+    Call(Box<Expression>, Vec<Expression>, Vec<(String, Expression)>),
+    /// `...list` among the arguments of a call, passes the elements of the list
+    /// as positional arguments
+    Spread(Box<Expression>),
+    /// `object.name`
+    Get(Box<Expression>, String),
+    /// `object.name = value`
+    Set(Box<Expression>, String, Box<Expression>),
+    /// `object[index]`
+    Index(Box<Expression>, Box<Expression>),
+    /// `object[index] = value`
+    SetIndex(Box<Expression>, Box<Expression>, Box<Expression>),
+    /// `object?.name`, only valid inside a `NullAwareChain`
+    NullAwareGet(Box<Expression>, String),
+    /// `object?[index]`, only valid inside a `NullAwareChain`
+    NullAwareIndex(Box<Expression>, Box<Expression>),
+    /// A member chain holding at least one `?.` or `?[`, such as
+    /// `user?.address.city`. When a null-aware access finds `nil` the rest of
+    /// the chain is skipped and the whole chain evaluates to `nil`.
+    NullAwareChain(Box<Expression>),
+    /// `target += value`, where the target is a variable, a property or an
+    /// indexed element. The receiver and index of the target are evaluated
+    /// once, then read, combined with the value and written back. Prefix
+    /// `++target` and `--target` are parsed as `target += 1` and `target -= 1`.
+    CompoundAssign(Box<Expression>, AssignOperator, Box<Expression>),
+    /// `target++`, updates the target like `CompoundAssign` but evaluates to
+    /// its previous value
+    Postfix(Box<Expression>, PostfixOperator),
+    /// `[a, b] = value`, assigns the bindings of the pattern to existing variables
+    Destructure(Box<Pattern>, Box<Expression>),
     Logical(Box<Expression>, LogicalOperator, Box<Expression>),
+    /// `receiver..section..section`, evaluates the receiver once, then every
+    /// section against it in order, and evaluates to the receiver
+    Cascade(Box<Expression>, Vec<Expression>),
+    /// The receiver a cascade section operates on, `..add(1)` is the call
+    /// `CascadeReceiver.add(1)`
+    CascadeReceiver,
+    /// `value is Type`, `value is! Type` or `value as Type`
+    TypeCheck(Box<Expression>, TypeOperator, Type),
+    /// `condition ? then : otherwise`, only the selected branch is evaluated
+    Ternary(Box<Expression>, Box<Expression>, Box<Expression>),
+    Range(Box<Expression>, RangeOperator, Box<Expression>),
+    /// `throw value`, raises any value as an exception. The innermost enclosing
+    /// `try` with a `catch` clause handles it, after the `finally` clauses of
+    /// the statements it leaves have run.
+    Throw(Box<Expression>),
+    /// `await future`, suspends the enclosing async function until the future
+    /// completes and evaluates to its value, a value that isn't a future as is
+    Await(Box<Expression>),
+    /// `switch (value) { pattern => result, ... }`, evaluates to the result of the
+    /// first matching arm
+    Switch(Box<Expression>, Vec<SwitchArm>),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SwitchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub body: Expression,
 }
 
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum BinaryOperator {
+    /// Always produces a double, even for two integers
     Slash,
     Star,
     Plus,
     Minus,
+    /// Euclidean modulo, the result is never negative, like Dart's `%`
+    Percent,
+    /// Exponentiation, an integer when both operands are integers and the
+    /// exponent is not negative, a double otherwise
+    StarStar,
+    /// Bitwise operators only accept integers, they operate on their 64 bits
+    /// two's complement representation
+    Ampersand,
+    Pipe,
+    Caret,
+    LessLess,
+    /// Arithmetic (sign extending) right shift
+    GreaterGreater,
+    /// Logical (zero filling) right shift
+    GreaterGreaterGreater,
     Greater,
     GreaterEqual,
     Less,
@@ -34,20 +126,99 @@ pub enum BinaryOperator {
 #[derive(Clone, PartialEq, Debug)]
 pub enum UnaryOperator {
     Bang,
-    Minus
+    Minus,
+    /// Bitwise complement, integers only
+    Tilde,
 }
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum LogicalOperator {
     And,
     Or,
+    /// `a ?? b`, evaluates `b` only when `a` is `nil`
+    QuestionQuestion,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum AssignOperator {
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    /// `??=`, only assigns when the target is `nil`
+    QuestionQuestion,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum PostfixOperator {
+    PlusPlus,
+    MinusMinus,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum TypeOperator {
+    Is,
+    IsNot,
+    /// Evaluates to the value itself, raises an error when it is not of the type
+    As,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum RangeOperator {
+    /// `a..b`, the end is excluded
+    DotDot,
+    /// `a..=b`, the end is included
+    DotDotEqual,
 }
 
 impl fmt::Display for LogicalOperator {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             LogicalOperator::And => write!(f, "and"),
-            LogicalOperator::Or => write!(f, "or")   
+            LogicalOperator::Or => write!(f, "or"),
+            LogicalOperator::QuestionQuestion => write!(f, "??")
+        }
+    }
+}
+
+impl fmt::Display for AssignOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AssignOperator::Plus => write!(f, "+="),
+            AssignOperator::Minus => write!(f, "-="),
+            AssignOperator::Star => write!(f, "*="),
+            AssignOperator::Slash => write!(f, "/="),
+            AssignOperator::Percent => write!(f, "%="),
+            AssignOperator::QuestionQuestion => write!(f, "??=")
+        }
+    }
+}
+
+impl fmt::Display for PostfixOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PostfixOperator::PlusPlus => write!(f, "++"),
+            PostfixOperator::MinusMinus => write!(f, "--")
+        }
+    }
+}
+
+impl fmt::Display for TypeOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TypeOperator::Is => write!(f, "is"),
+            TypeOperator::IsNot => write!(f, "is!"),
+            TypeOperator::As => write!(f, "as")
+        }
+    }
+}
+
+impl fmt::Display for RangeOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RangeOperator::DotDot => write!(f, ".."),
+            RangeOperator::DotDotEqual => write!(f, "..=")
         }
     }
 }
@@ -56,7 +227,8 @@ impl fmt::Display for UnaryOperator {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             UnaryOperator::Minus => write!(f, "-"),
-            UnaryOperator::Bang => write!(f, "!")   
+            UnaryOperator::Bang => write!(f, "!"),
+            UnaryOperator::Tilde => write!(f, "~")
         }
     }
 }
@@ -68,6 +240,14 @@ impl fmt::Display for BinaryOperator {
             BinaryOperator::Star => write!(f, "*"),
             BinaryOperator::Plus => write!(f, "+"),
             BinaryOperator::Minus => write!(f, "-"),
+            BinaryOperator::Percent => write!(f, "%"),
+            BinaryOperator::StarStar => write!(f, "**"),
+            BinaryOperator::Ampersand => write!(f, "&"),
+            BinaryOperator::Pipe => write!(f, "|"),
+            BinaryOperator::Caret => write!(f, "^"),
+            BinaryOperator::LessLess => write!(f, "<<"),
+            BinaryOperator::GreaterGreater => write!(f, ">>"),
+            BinaryOperator::GreaterGreaterGreater => write!(f, ">>>"),
             BinaryOperator::Greater => write!(f, ">"),
             BinaryOperator::GreaterEqual => write!(f, ">="),
             BinaryOperator::Less => write!(f, "<"),
@@ -76,4 +256,143 @@ impl fmt::Display for BinaryOperator {
             BinaryOperator::EqualEqual => write!(f, "==")   
         }
     }
-}
\ No newline at end of file
+}
+
+impl BinaryOperator {
+    /// How tightly the operator binds its operands, ranked like the parser ranks it.
+    fn precedence(&self) -> u8 {
+        match self {
+            BinaryOperator::EqualEqual | BinaryOperator::BangEqual => 1,
+            BinaryOperator::Greater | BinaryOperator::GreaterEqual | BinaryOperator::Less | BinaryOperator::LessEqual => 2,
+            BinaryOperator::Pipe => 3,
+            BinaryOperator::Caret => 4,
+            BinaryOperator::Ampersand => 5,
+            BinaryOperator::LessLess | BinaryOperator::GreaterGreater | BinaryOperator::GreaterGreaterGreater => 6,
+            BinaryOperator::Plus | BinaryOperator::Minus => 7,
+            BinaryOperator::Star | BinaryOperator::Slash | BinaryOperator::Percent => 8,
+            BinaryOperator::StarStar => 10,
+        }
+    }
+}
+
+/// How tightly an expression holds together when written as an operand: a
+/// prefix operator between the binary ones, anything looser below them all.
+fn binding_power(expression: &Expression) -> u8 {
+    match expression {
+        Expression::Binary(_, operator, _) => operator.precedence(),
+        Expression::Unary(..) | Expression::Await(_) => 9,
+        Expression::Logical(..)
+        | Expression::Ternary(..)
+        | Expression::Assign(..)
+        | Expression::Set(..)
+        | Expression::SetIndex(..)
+        | Expression::CompoundAssign(..)
+        | Expression::Destructure(..)
+        | Expression::Range(..)
+        | Expression::TypeCheck(..)
+        | Expression::Cascade(..)
+        | Expression::Throw(_) => 0,
+        _ => u8::MAX,
+    }
+}
+
+/// Writes an operand, in parentheses when the tree holds it tighter than its
+/// spelling would, as it does for the folded or nested operands no grouping keeps.
+fn write_operand(f: &mut fmt::Formatter, operand: &Expression, parenthesized: bool) -> fmt::Result {
+    match parenthesized {
+        true => write!(f, "({})", operand),
+        false => write!(f, "{}", operand),
+    }
+}
+
+/// Writes expressions back as source, as assertion failures quote them.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expression::Binary(left, operator, right) => {
+                // `**` groups to the right, the other operators to the left
+                let precedence = operator.precedence();
+                let right_associative = *operator == BinaryOperator::StarStar;
+                let (left_power, right_power) = (binding_power(left), binding_power(right));
+
+                write_operand(f, left, left_power < precedence || right_associative && left_power == precedence)?;
+                write!(f, " {} ", operator)?;
+                write_operand(f, right, right_power < precedence || !right_associative && right_power == precedence)
+            }
+            Expression::Grouping(expression) => write!(f, "({})", expression),
+            Expression::String(string) => write!(f, "\"{}\"", string),
+            Expression::Boolean(boolean) => write!(f, "{}", boolean),
+            // a double keeps its decimal point, `2.0` is not the int `2`
+            Expression::Number(number) if number.fract() == 0.0 && number.is_finite() => write!(f, "{:.1}", number),
+            Expression::Number(number) => write!(f, "{}", number),
+            Expression::Integer(integer) => write!(f, "{}", integer),
+            Expression::Nil => write!(f, "nil"),
+            Expression::List(elements) => write!(f, "[{}]", join(elements)),
+            Expression::Record(positional, named) => {
+                let mut fields: Vec<String> = positional.iter().map(|field| field.to_string()).collect();
+                fields.extend(named.iter().map(|(name, field)| format!("{}: {}", name, field)));
+
+                match (positional.len(), named.len()) {
+                    (1, 0) => write!(f, "({},)", fields[0]),
+                    _ => write!(f, "({})", fields.join(", ")),
+                }
+            }
+            Expression::Unary(operator, right) => {
+                write!(f, "{}", operator)?;
+                write_operand(f, right, binding_power(right) != u8::MAX)
+            }
+            Expression::Variable(name) => write!(f, "{}", name),
+            Expression::This => write!(f, "this"),
+            Expression::Super => write!(f, "super"),
+            Expression::Assign(name, value) => write!(f, "{} = {}", name, value),
+            Expression::Call(callee, arguments, named) => {
+                let mut arguments: Vec<String> = arguments.iter().map(|argument| argument.to_string()).collect();
+                arguments.extend(named.iter().map(|(name, argument)| format!("{}: {}", name, argument)));
+                write!(f, "{}({})", callee, arguments.join(", "))
+            }
+            Expression::Get(object, name) => write!(f, "{}.{}", object, name),
+            Expression::Set(object, name, value) => write!(f, "{}.{} = {}", object, name, value),
+            Expression::Index(object, index) => write!(f, "{}[{}]", object, index),
+            Expression::SetIndex(object, index, value) => write!(f, "{}[{}] = {}", object, index, value),
+            Expression::NullAwareGet(object, name) => write!(f, "{}?.{}", object, name),
+            Expression::NullAwareIndex(object, index) => write!(f, "{}?[{}]", object, index),
+            Expression::NullAwareChain(chain) => write!(f, "{}", chain),
+            Expression::CompoundAssign(target, operator, value) => write!(f, "{} {} {}", target, operator, value),
+            Expression::Postfix(target, operator) => write!(f, "{}{}", target, operator),
+            Expression::Destructure(pattern, value) => write!(f, "{} = {}", pattern, value),
+            Expression::Logical(left, operator, right) => write!(f, "{} {} {}", left, operator, right),
+            // sections start with the `.` following their empty receiver
+            Expression::Cascade(receiver, sections) => {
+                write!(f, "{}", receiver)?;
+                for section in sections {
+                    write!(f, ".{}", section)?;
+                }
+                Ok(())
+            }
+            Expression::CascadeReceiver => Ok(()),
+            Expression::TypeCheck(value, operator, target) => write!(f, "{} {} {}", value, operator, target),
+            Expression::Ternary(condition, then_branch, else_branch) => {
+                write!(f, "{} ? {} : {}", condition, then_branch, else_branch)
+            }
+            Expression::Range(start, operator, end) => write!(f, "{}{}{}", start, operator, end),
+            Expression::Throw(value) => write!(f, "throw {}", value),
+            Expression::Await(value) => write!(f, "await {}", value),
+            Expression::Spread(value) => write!(f, "...{}", value),
+            Expression::Switch(value, arms) => {
+                let arms: Vec<String> = arms
+                    .iter()
+                    .map(|arm| match &arm.guard {
+                        Some(guard) => format!("{} when {} => {}", arm.pattern, guard, arm.body),
+                        None => format!("{} => {}", arm.pattern, arm.body),
+                    })
+                    .collect();
+                write!(f, "switch ({}) {{ {} }}", value, arms.join(", "))
+            }
+        }
+    }
+}
+
+fn join(expressions: &[Expression]) -> String {
+    let expressions: Vec<String> = expressions.iter().map(|expression| expression.to_string()).collect();
+    expressions.join(", ")
+}
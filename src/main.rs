@@ -1,16 +1,59 @@
+mod checker;
+mod diagnostic;
 mod expression;
+mod module;
 mod parser;
+mod pattern;
+mod resolver;
 mod scanner;
+mod statement;
 pub mod token;
+mod types;
 
-use std::io;
+use std::{
+    env, io,
+    path::{Path, PathBuf},
+    process,
+};
 
-use parser::{parse, Parser};
+use checker::{check_program, Checker};
+use module::{load_program, Loader};
+use parser::{parse_program, Parser};
+use resolver::{resolve_program, Resolver};
 use scanner::Scanner;
 
-use crate::expression::Expression;
+use crate::{expression::Expression, statement::Statement};
+
+const USAGE: &str = "usage: quillscript [--no-asserts] [--path <directory>]... [script]";
 
 fn main() {
+    // production runs drop every assert before anything else sees it
+    let mut asserts = true;
+    // imports not found next to the importing file are looked up in these directories
+    let mut search_path: Vec<PathBuf> = match env::var_os("QUILL_PATH") {
+        Some(paths) => env::split_paths(&paths).collect(),
+        None => Vec::new(),
+    };
+    let mut script = None;
+
+    let mut arguments = env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--no-asserts" => asserts = false,
+            "--path" => match arguments.next() {
+                Some(directory) => search_path.push(PathBuf::from(directory)),
+                None => usage_error("option '--path' expects a directory"),
+            },
+            option if option.starts_with("--") => usage_error(&format!("unknown option '{}'", option)),
+            _ => script = Some(PathBuf::from(argument)),
+        }
+    }
+
+    if let Some(script) = script {
+        run_file(&script, search_path, asserts);
+        return;
+    }
+
     println!("Quillscript REPL (type 'exit' to stop):");
 
     loop {
@@ -29,13 +72,38 @@ fn main() {
 
                 let tokens = scanner.scan();
 
-                let mut parser = Parser {
-                    tokens: tokens.clone(),
-                    position: 0,
-                };
+                let mut parser = Parser::new(tokens.clone());
+                parser.asserts = asserts;
+
+                let mut statements = parse_program(&mut parser);
+                if !parser.diagnostics.is_empty() {
+                    for diagnostic in &parser.diagnostics {
+                        println!("{}", diagnostic);
+                    }
+                    continue;
+                }
+
+                let mut resolver = Resolver::new();
+                resolve_program(&mut resolver, &mut statements);
+                if !resolver.diagnostics.is_empty() {
+                    for diagnostic in &resolver.diagnostics {
+                        println!("{}", diagnostic);
+                    }
+                    continue;
+                }
+
+                let mut checker = Checker::new();
+                check_program(&mut checker, &statements);
+                if !checker.diagnostics.is_empty() {
+                    for diagnostic in &checker.diagnostics {
+                        println!("{}", diagnostic);
+                    }
+                    continue;
+                }
 
-                let expression = parse(&mut parser);
-                print_ast(expression);
+                for statement in statements {
+                    print_statement(statement);
+                }
             }
             Err(error) => {
                 println!("Error reading input: {}", error);
@@ -45,6 +113,55 @@ fn main() {
     }
 }
 
+/// Reports a bad command line and exits.
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    process::exit(2);
+}
+
+/// Loads a script with the modules it imports and checks each module after
+/// the ones it imports, stopping at the first module with errors.
+fn run_file(path: &Path, search_path: Vec<PathBuf>, asserts: bool) {
+    let mut loader = Loader::new(search_path, asserts);
+    load_program(&mut loader, path);
+    if !loader.diagnostics.is_empty() {
+        for (path, diagnostic) in &loader.diagnostics {
+            println!("{}: {}", path.display(), diagnostic);
+        }
+        return;
+    }
+
+    let mut modules = loader.modules;
+    for module in &mut modules {
+        let mut resolver = Resolver::new();
+        resolve_program(&mut resolver, &mut module.statements);
+
+        let mut checker = Checker::new();
+        check_program(&mut checker, &module.statements);
+
+        let diagnostics: Vec<_> = resolver.diagnostics.iter().chain(&checker.diagnostics).collect();
+        if !diagnostics.is_empty() {
+            for diagnostic in diagnostics {
+                println!("{}: {}", module.path.display(), diagnostic);
+            }
+            return;
+        }
+    }
+
+    if let Some(program) = modules.pop() {
+        for statement in program.statements {
+            print_statement(statement);
+        }
+    }
+}
+
+fn print_statement(statement: Statement) {
+    match statement {
+        Statement::Expression(expression, _) => print_ast(expression),
+        _ => println!("{:?}", statement),
+    }
+}
+
 fn print_ast(expression: Expression) {
     match expression {
         Expression::Grouping(expr) => {
@@ -61,6 +178,7 @@ fn print_ast(expression: Expression) {
         Expression::Number(num) => {
             println!("{}", num)
         }
+        Expression::Integer(integer) => println!("{}", integer),
         Expression::Boolean(boolean) => println!("{}", boolean),
         Expression::Logical(left, operator, right) => {
             print_ast(*left);
@@ -0,0 +1,270 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    diagnostic::Diagnostic,
+    parser::{parse_program, Parser},
+    scanner::Scanner,
+    statement::{Import, Statement},
+};
+
+/// A source file, parsed, whose imports know what the modules they load export.
+pub struct Module {
+    pub path: PathBuf,
+    pub statements: Vec<Statement>,
+    /// names declared by the top-level `export` statements
+    pub exports: Vec<String>,
+}
+
+/// Loads a program and the modules it imports, transitively.
+///
+/// An import is looked up relative to the directory of the importing file,
+/// then in each directory of the search path in order. A file is loaded once
+/// however many modules import it, and an import cycle is reported with the
+/// chain of files that closes it.
+pub struct Loader {
+    search_path: Vec<PathBuf>,
+    /// whether `assert` statements are kept, see `Parser::asserts`
+    asserts: bool,
+    /// modules loaded, each after the modules it imports
+    pub modules: Vec<Module>,
+    /// files being loaded, the one importing the next first
+    loading: Vec<PathBuf>,
+    /// errors of every file, with the file they were found in
+    pub diagnostics: Vec<(PathBuf, Diagnostic)>,
+}
+
+impl Loader {
+    pub fn new(search_path: Vec<PathBuf>, asserts: bool) -> Loader {
+        Loader {
+            search_path,
+            asserts,
+            modules: Vec::new(),
+            loading: Vec::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, path: &Path, line: i32, message: String) {
+        self.diagnostics.push((path.to_path_buf(), Diagnostic { line, message }));
+    }
+
+    fn module(&self, path: &Path) -> Option<&Module> {
+        self.modules.iter().find(|module| module.path == path)
+    }
+}
+
+/// Loads the program in `path`, it is the last module loaded.
+pub fn load_program(loader: &mut Loader, path: &Path) {
+    match path.canonicalize() {
+        Ok(path) => load_module(loader, path),
+        Err(error) => loader.error(path, 1, format!("can't read '{}': {}", path.display(), error)),
+    }
+}
+
+fn load_module(loader: &mut Loader, path: PathBuf) {
+    let source = match fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(error) => {
+            loader.error(&path, 1, format!("can't read '{}': {}", path.display(), error));
+            return;
+        }
+    };
+
+    let mut scanner = Scanner::new(source);
+    let mut parser = Parser::new(scanner.scan().clone());
+    parser.asserts = loader.asserts;
+
+    let mut statements = parse_program(&mut parser);
+
+    // the imports of a file with syntax errors may be incomplete, they are not followed
+    if parser.diagnostics.is_empty() {
+        loader.loading.push(path.clone());
+        for statement in &mut statements {
+            if let Statement::Import(import) = statement {
+                load_import(loader, &path, import);
+            }
+        }
+        loader.loading.pop();
+    }
+    for diagnostic in parser.diagnostics {
+        loader.diagnostics.push((path.clone(), diagnostic));
+    }
+
+    let exports = statements.iter().flat_map(exported_names).collect();
+    loader.modules.push(Module {
+        path,
+        statements,
+        exports,
+    });
+}
+
+fn load_import(loader: &mut Loader, importer: &Path, import: &mut Import) {
+    let path = match find_module(loader, importer, &import.path) {
+        Some(path) => path,
+        None => {
+            loader.error(importer, import.line, format!("can't find module '{}'", import.path));
+            return;
+        }
+    };
+
+    if let Some(start) = loader.loading.iter().position(|loading| *loading == path) {
+        let cycle: Vec<String> = loader.loading[start..]
+            .iter()
+            .chain([&path])
+            .map(|path| file_name(path))
+            .collect();
+        loader.error(importer, import.line, format!("import cycle: {}", cycle.join(" -> ")));
+        return;
+    }
+
+    if loader.module(&path).is_none() {
+        load_module(loader, path.clone());
+    }
+    import.exports = loader.module(&path).map(|module| module.exports.clone());
+}
+
+/// The file an import names, canonical so every import of it finds the same module.
+fn find_module(loader: &Loader, importer: &Path, name: &str) -> Option<PathBuf> {
+    importer
+        .parent()
+        .into_iter()
+        .chain(loader.search_path.iter().map(PathBuf::as_path))
+        .map(|directory| directory.join(name))
+        .find(|path| path.is_file())
+        .and_then(|path| path.canonicalize().ok())
+}
+
+fn file_name(path: &Path) -> String {
+    match path.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => path.display().to_string(),
+    }
+}
+
+fn exported_names(statement: &Statement) -> Vec<String> {
+    let declaration = match statement {
+        Statement::Export(declaration) => declaration,
+        _ => return Vec::new(),
+    };
+
+    match &**declaration {
+        Statement::Var(_, name, ..) => vec![name.clone()],
+        Statement::Destructure(_, pattern, ..) => pattern.bindings().into_iter().map(String::from).collect(),
+        Statement::Function(function) => vec![function.name.clone()],
+        Statement::Class(class) => vec![class.name.clone()],
+        Statement::Enum(declaration) => vec![declaration.name.clone()],
+        Statement::Extension(extension) => vec![extension.name.clone()],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    /// A directory of its own for the files of a test, removed when dropped.
+    struct Files(PathBuf);
+
+    impl Files {
+        fn new(name: &str, files: &[(&str, &str)]) -> Files {
+            let directory = env::temp_dir().join(format!("quillscript-{}-{}", name, process::id()));
+            for (file, source) in files {
+                let path = directory.join(file);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, source).unwrap();
+            }
+            Files(directory)
+        }
+    }
+
+    impl Drop for Files {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn messages(loader: &Loader) -> Vec<String> {
+        loader
+            .diagnostics
+            .iter()
+            .map(|(_, diagnostic)| diagnostic.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_imports() {
+        let files = Files::new(
+            "imports",
+            &[
+                ("main.ql", "import \"a.ql\" as a;\nimport \"b.ql\";\nprint a.twice(2);"),
+                ("a.ql", "import \"shared.ql\";\nexport func twice(x) { return x * 2; }"),
+                ("b.ql", "import \"shared.ql\" as s;\nvar hidden = 1;"),
+                ("shared.ql", "export final shared = 21;\nexport var [first, second] = [1, 2];"),
+            ],
+        );
+
+        let mut loader = Loader::new(Vec::new(), true);
+        load_program(&mut loader, &files.0.join("main.ql"));
+        assert_eq!(messages(&loader), Vec::<String>::new());
+
+        // shared.ql is loaded once, before the modules importing it
+        let names: Vec<String> = loader.modules.iter().map(|module| file_name(&module.path)).collect();
+        assert_eq!(names, vec!["shared.ql", "a.ql", "b.ql", "main.ql"]);
+        assert_eq!(loader.modules[0].exports, vec!["shared", "first", "second"]);
+        assert_eq!(loader.modules[2].exports, Vec::<String>::new());
+
+        match &loader.modules[3].statements[0] {
+            Statement::Import(import) => assert_eq!(import.exports, Some(vec!["twice".to_string()])),
+            statement => panic!("expected an import, found {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn test_search_path() {
+        let files = Files::new(
+            "search-path",
+            &[
+                ("app/main.ql", "import \"lib.ql\" as lib;\nimport \"missing.ql\";"),
+                ("libraries/lib.ql", "export func help() {}"),
+            ],
+        );
+
+        let mut loader = Loader::new(Vec::new(), true);
+        load_program(&mut loader, &files.0.join("app/main.ql"));
+        assert_eq!(
+            messages(&loader),
+            vec!["can't find module 'lib.ql'", "can't find module 'missing.ql'"]
+        );
+
+        let mut loader = Loader::new(vec![files.0.join("libraries")], true);
+        load_program(&mut loader, &files.0.join("app/main.ql"));
+        assert_eq!(messages(&loader), vec!["can't find module 'missing.ql'"]);
+        assert_eq!(loader.modules.len(), 2);
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let files = Files::new(
+            "cycle",
+            &[
+                ("main.ql", "import \"a.ql\";"),
+                ("a.ql", "import \"b.ql\";"),
+                ("b.ql", "var x = 1;\nimport \"a.ql\";"),
+            ],
+        );
+
+        let mut loader = Loader::new(Vec::new(), true);
+        load_program(&mut loader, &files.0.join("main.ql"));
+
+        let (path, diagnostic) = &loader.diagnostics[0];
+        assert_eq!(loader.diagnostics.len(), 1);
+        assert_eq!(file_name(path), "b.ql");
+        assert_eq!(diagnostic.line, 2);
+        assert_eq!(diagnostic.message, "import cycle: a.ql -> b.ql -> a.ql");
+    }
+}
@@ -1,45 +1,1431 @@
 use core::panic;
 
 use crate::{
-    expression::{BinaryOperator, Expression, UnaryOperator, LogicalOperator},
+    diagnostic::Diagnostic,
+    expression::{
+        AssignOperator, BinaryOperator, Expression, LogicalOperator, PostfixOperator, RangeOperator,
+        SwitchArm, TypeOperator, UnaryOperator,
+    },
+    pattern::Pattern,
+    statement::{
+        Catch, Class, Enum, EnumValue, Extension, Field, Function, Import, Mutability, Parameter, ParameterKind, Statement,
+        SwitchCase, Try,
+    },
     token::Token,
+    types::Type,
 };
 
 #[derive(PartialEq, PartialOrd)]
 pub enum Precedence {
     None,
     Assign,
+    Cascade,
+    Ternary,
+    IfNull,
     Or,
     And,
     Equality,
     Comparison,
+    Range,
+    BitwiseOr,
+    BitwiseXor,
+    BitwiseAnd,
+    Shift,
     Term,
     Factor,
     Unary,
+    /// `**` binds tighter than a prefix operator on its left, `-2 ** 2` is `-(2 ** 2)`
+    Exponent,
     Call
 }
 
 pub struct Parser {
     pub tokens: Vec<Token>,
+    pub lines: Vec<i32>,
     pub position: usize,
+    pub diagnostics: Vec<Diagnostic>,
+    /// labels of the loops enclosing the statement being parsed, innermost last
+    loops: Vec<Option<String>>,
+    /// number of switch statements enclosing the statement being parsed
+    switches: usize,
+    /// number of functions enclosing the statement being parsed
+    functions: usize,
+    /// whether the innermost function enclosing the statement being parsed is a generator
+    generator: bool,
+    /// whether the innermost function enclosing the statement being parsed is async
+    asynchronous: bool,
+    /// number of classes enclosing the statement being parsed
+    classes: usize,
+    /// type parameters of the enclosing generic classes and functions
+    type_parameters: Vec<String>,
+    /// number of cascade sections whose assigned value is being parsed, a call
+    /// section after the value belongs to the cascade
+    cascades: usize,
+    /// number of `:` expected after the expression being parsed, by the enclosing
+    /// conditionals and case guards, see `is_conditional_index`
+    colons: usize,
+    /// whether `assert` statements are kept, when off they are parsed then dropped
+    pub asserts: bool,
+    /// whether a syntax error was reported in the statement being parsed, the
+    /// errors following it up to the next statement are its consequences
+    panicking: bool,
 }
 
 impl Parser {
+    pub fn new(tokens: Vec<Token>) -> Parser {
+        let mut line = 1;
+        let mut lines = Vec::new();
+        let mut filtered = Vec::new();
+
+        // line breaks only matter for diagnostics, statements are delimited by semicolons
+        for token in tokens {
+            match token {
+                Token::Line => line += 1,
+                _ => {
+                    filtered.push(token);
+                    lines.push(line);
+                }
+            }
+        }
+
+        Parser {
+            tokens: filtered,
+            lines,
+            position: 0,
+            diagnostics: Vec::new(),
+            loops: Vec::new(),
+            switches: 0,
+            functions: 0,
+            generator: false,
+            asynchronous: false,
+            classes: 0,
+            type_parameters: Vec::new(),
+            cascades: 0,
+            colons: 0,
+            asserts: true,
+            panicking: false,
+        }
+    }
+
+    #[allow(clippy::needless_return, clippy::assign_op_pattern)]
     pub fn next(&mut self) -> &Token {
         let token = self.tokens.get(self.position).unwrap_or(&Token::Eof);
         self.position = self.position + 1;
         return token;
     }
 
-    pub fn peek(&mut self) -> &Token {
-        return self.tokens.get(self.position).unwrap_or(&Token::Eof);
+    #[allow(clippy::needless_return)]
+    pub fn peek(&self) -> &Token {
+        return self.tokens.get(self.position).unwrap_or(&Token::Eof);
+    }
+
+    pub fn peek_next(&self) -> &Token {
+        self.peek_at(1)
+    }
+
+    pub fn peek_at(&self, offset: usize) -> &Token {
+        self.tokens.get(self.position + offset).unwrap_or(&Token::Eof)
+    }
+
+    /// Line of the next token, or of the last one once the input is exhausted.
+    pub fn line(&self) -> i32 {
+        match self.lines.get(self.position) {
+            Some(line) => *line,
+            None => self.lines.last().copied().unwrap_or(1),
+        }
+    }
+
+    fn error(&mut self, line: i32, message: String) {
+        self.diagnostics.push(Diagnostic::new(line, message));
+    }
+
+    /// Line of the last token consumed.
+    fn previous_line(&self) -> i32 {
+        match self.position.checked_sub(1).and_then(|position| self.lines.get(position)) {
+            Some(line) => *line,
+            None => self.line(),
+        }
+    }
+
+    /// Reports a syntax error at the next token, unless one was already
+    /// reported in the statement being parsed.
+    fn syntax_error(&mut self, message: &str) {
+        let line = self.line();
+        self.syntax_error_at(line, message);
+    }
+
+    fn syntax_error_at(&mut self, line: i32, message: &str) {
+        if !self.panicking {
+            self.error(line, message.to_string());
+        }
+        self.panicking = true;
+    }
+
+    /// Whether a list ends at the next token: its closing token, the end of the
+    /// input, or anything after a syntax error.
+    fn at_end_of(&self, closing: &Token) -> bool {
+        self.peek() == closing || self.peek() == &Token::Eof || self.panicking
+    }
+
+    /// Consumes the expected token, a missing one is reported on the line of
+    /// what it should follow.
+    fn consume(&mut self, token: Token, message: &str) {
+        if self.peek() == &token {
+            self.next();
+        } else {
+            let line = self.previous_line();
+            self.syntax_error_at(line, message);
+        }
+    }
+
+    /// Consumes an identifier, a missing one is reported and reads as an empty name.
+    fn consume_identifier(&mut self, message: &str) -> String {
+        match self.peek() {
+            Token::Identifier(identifier) => {
+                let identifier = identifier.clone();
+                self.next();
+                identifier
+            }
+            _ => {
+                self.syntax_error(message);
+                String::new()
+            }
+        }
+    }
+
+    /// Consumes the name of a property, an identifier or a keyword.
+    fn consume_property_name(&mut self, message: &str) -> String {
+        match self.peek() {
+            Token::Identifier(_) => self.consume_identifier(message),
+            token if token.is_keyword() => {
+                let name = token.to_string();
+                self.next();
+                name
+            }
+            _ => {
+                self.syntax_error(message);
+                String::new()
+            }
+        }
+    }
+}
+
+#[allow(clippy::needless_return)]
+pub fn parse(parser: &mut Parser) -> Expression {
+    return parse_expr(parser, Precedence::None);
+}
+
+pub fn parse_program(parser: &mut Parser) -> Vec<Statement> {
+    let mut statements = Vec::new();
+
+    while parser.peek() != &Token::Eof {
+        let start = parser.position;
+        let statement = match parser.peek() {
+            Token::Import => parse_import(parser),
+            Token::Export => parse_export(parser),
+            _ => parse_declaration(parser),
+        };
+        statements.push(statement);
+
+        if parser.panicking {
+            synchronize(parser, start);
+        }
+    }
+
+    statements
+}
+
+/// Skips the rest of a statement with a syntax error, up to where the next
+/// one likely starts, so that the errors of the following statements are
+/// reported too.
+fn synchronize(parser: &mut Parser, start: usize) {
+    parser.panicking = false;
+
+    // a statement that consumed nothing loses its first token, so parsing moves on
+    if parser.position == start {
+        parser.next();
+    }
+
+    // braces opened while skipping are skipped up to their closing brace
+    let mut depth = 0;
+    while parser.peek() != &Token::Eof {
+        if depth == 0 && (parser.tokens.get(parser.position - 1) == Some(&Token::Semicolon) || starts_statement(parser.peek())) {
+            return;
+        }
+
+        match parser.next() {
+            Token::LeftBrace => depth += 1,
+            Token::RightBrace => {
+                depth -= 1;
+                // a closed block ends the statement, along with a semicolon after it
+                if depth == 0 {
+                    if parser.peek() == &Token::Semicolon {
+                        parser.next();
+                    }
+                    return;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether the token likely starts a statement, or ends the enclosing block.
+fn starts_statement(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Var
+            | Token::Final
+            | Token::Const
+            | Token::Func
+            | Token::Async
+            | Token::Class
+            | Token::Enum
+            | Token::Import
+            | Token::Export
+            | Token::Print
+            | Token::If
+            | Token::While
+            | Token::For
+            | Token::Break
+            | Token::Continue
+            | Token::Switch
+            | Token::Case
+            | Token::Default
+            | Token::Return
+            | Token::Yield
+            | Token::Try
+            | Token::Assert
+            | Token::RightBrace
+    )
+}
+
+fn parse_declaration(parser: &mut Parser) -> Statement {
+    let start = parser.position;
+    let statement = match parser.peek() {
+        Token::Var | Token::Final | Token::Const => parse_var_declaration(parser),
+        Token::Func | Token::Async => parse_function_declaration(parser),
+        Token::Class => parse_class_declaration(parser),
+        Token::Enum => parse_enum_declaration(parser),
+        Token::Import | Token::Export => {
+            let line = parser.line();
+            let keyword = parser.peek().to_string();
+            parser.error(line, format!("'{}' is only allowed at the top level", keyword));
+
+            match parser.peek() {
+                Token::Import => parse_import(parser),
+                _ => parse_export(parser),
+            }
+        }
+        // `extension` is only a keyword when a name follows it
+        Token::Identifier(name) if name == "extension" && matches!(parser.peek_next(), Token::Identifier(_)) => {
+            parse_extension_declaration(parser)
+        }
+        _ => parse_statement(parser),
+    };
+
+    if parser.panicking {
+        synchronize(parser, start);
+    }
+
+    statement
+}
+
+fn parse_import(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Import, "expect 'import'");
+
+    let path = match parser.peek() {
+        Token::String(path) => {
+            let path = path.clone();
+            parser.next();
+            path
+        }
+        _ => {
+            parser.syntax_error("expect module path after 'import'");
+            String::new()
+        }
+    };
+
+    let alias = match parser.peek() {
+        Token::As => {
+            parser.next();
+            Some(parser.consume_identifier("expect module name after 'as'"))
+        }
+        _ => None,
+    };
+
+    parser.consume(Token::Semicolon, "expect ';' after import");
+    Statement::Import(Import {
+        path,
+        alias,
+        exports: None,
+        line,
+    })
+}
+
+fn parse_export(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Export, "expect 'export'");
+
+    let declaration = parse_declaration(parser);
+    match declaration {
+        Statement::Var(..)
+        | Statement::Destructure(..)
+        | Statement::Function(_)
+        | Statement::Class(_)
+        | Statement::Enum(_)
+        | Statement::Extension(_) => {}
+        _ => parser.error(line, "only declarations can be exported".to_string()),
+    }
+
+    Statement::Export(Box::new(declaration))
+}
+
+fn parse_var_declaration(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    let mutability = match parser.next() {
+        Token::Final => Mutability::Final,
+        Token::Const => Mutability::Const,
+        _ => Mutability::Var,
+    };
+
+    if matches!(parser.peek(), Token::LeftBracket | Token::LeftBrace) {
+        if mutability == Mutability::Const {
+            parser.error(line, "a destructuring declaration can't be const".to_string());
+        }
+        return parse_destructuring_declaration(parser, mutability, line);
+    }
+
+    let name = parser.consume_identifier("expect variable name");
+    parse_var_initializer(parser, mutability, name, line)
+}
+
+fn parse_destructuring_declaration(parser: &mut Parser, mutability: Mutability, line: i32) -> Statement {
+    let pattern = parse_pattern(parser);
+    check_bindings(parser, line, &pattern);
+    parser.consume(Token::Equal, "expect '=' after destructuring pattern");
+    let value = parse(parser);
+    parser.consume(Token::Semicolon, "expect ';' after variable declaration");
+    Statement::Destructure(mutability, pattern, value, line)
+}
+
+fn parse_var_initializer(parser: &mut Parser, mutability: Mutability, name: String, line: i32) -> Statement {
+    let annotation = parse_annotation(parser);
+
+    let initializer = match parser.peek() {
+        Token::Equal => {
+            parser.next();
+            Some(parse(parser))
+        }
+        _ => None,
+    };
+
+    if initializer.is_none() && mutability != Mutability::Var {
+        parser.error(line, format!("{} variable '{}' must be initialized", mutability, name));
+    }
+
+    parser.consume(Token::Semicolon, "expect ';' after variable declaration");
+    Statement::Var(mutability, name, annotation, initializer, line)
+}
+
+/// Parses the optional `: Type` following a declared name.
+fn parse_annotation(parser: &mut Parser) -> Option<Type> {
+    match parser.peek() {
+        Token::Colon => {
+            parser.next();
+            Some(parse_type(parser))
+        }
+        _ => None,
+    }
+}
+
+fn parse_type(parser: &mut Parser) -> Type {
+    let named = match parser.peek() {
+        Token::LeftParen => parse_record_type(parser),
+        _ => {
+            let name = parser.consume_identifier("expect type name");
+            parse_named_type(parser, name)
+        }
+    };
+
+    match parser.peek() {
+        Token::Question => {
+            parser.next();
+            Type::Nullable(Box::new(named))
+        }
+        _ => named,
+    }
+}
+
+/// Parses `(int, String)` or `(x: int, y: int)`, spelled like the records it describes.
+fn parse_record_type(parser: &mut Parser) -> Type {
+    parser.consume(Token::LeftParen, "expect '('");
+
+    let mut positional = Vec::new();
+    let mut named = Vec::new();
+    while !parser.at_end_of(&Token::RightParen) {
+        match (parser.peek(), parser.peek_next()) {
+            (Token::Identifier(_), Token::Colon) => {
+                let name = parser.consume_identifier("expect field name");
+                parser.next();
+                named.push((name, parse_type(parser)));
+            }
+            _ => positional.push(parse_type(parser)),
+        }
+
+        if parser.peek() != &Token::RightParen {
+            parser.consume(Token::Comma, "expect ',' between record fields");
+        }
+    }
+
+    parser.consume(Token::RightParen, "expect ')' after record fields");
+    Type::Record(positional, named)
+}
+
+/// Resolves a type name, followed by its type arguments if it has any.
+fn parse_named_type(parser: &mut Parser, name: String) -> Type {
+    if parser.type_parameters.contains(&name) {
+        return Type::Parameter(name);
+    }
+
+    let base = Type::from_name(&name);
+    if parser.peek() != &Token::Less {
+        return base;
+    }
+
+    parser.next();
+    let mut arguments = Vec::new();
+    loop {
+        arguments.push(parse_type(parser));
+        if parser.peek() != &Token::Comma {
+            break;
+        }
+        parser.next();
+    }
+    parse_closing_angle(parser);
+
+    Type::Generic(Box::new(base), arguments)
+}
+
+/// Consumes the `>` closing type arguments. The scanner reads `List<List<int>>`
+/// with a single `>>` token, only its first `>` is consumed then.
+fn parse_closing_angle(parser: &mut Parser) {
+    let rest = match parser.peek() {
+        Token::Greater => None,
+        Token::GreaterGreater => Some(Token::Greater),
+        Token::GreaterGreaterGreater => Some(Token::GreaterGreater),
+        Token::GreaterEqual => Some(Token::Equal),
+        _ => {
+            parser.syntax_error("expect '>' after type arguments");
+            return;
+        }
+    };
+
+    match rest {
+        Some(rest) => parser.tokens[parser.position] = rest,
+        None => {
+            parser.next();
+        }
+    }
+}
+
+/// Parses the optional `<T, U>` following the name of a generic class or
+/// function and brings the parameters into scope, the caller removes them.
+fn parse_type_parameters(parser: &mut Parser) -> Vec<String> {
+    let mut type_parameters = Vec::new();
+    if parser.peek() != &Token::Less {
+        return type_parameters;
+    }
+
+    parser.next();
+    loop {
+        let line = parser.line();
+        let name = parser.consume_identifier("expect type parameter name");
+        if type_parameters.contains(&name) {
+            parser.error(line, format!("type parameter '{}' is already declared", name));
+        }
+        type_parameters.push(name);
+
+        if parser.peek() != &Token::Comma {
+            break;
+        }
+        parser.next();
+    }
+    parser.consume(Token::Greater, "expect '>' after type parameters");
+
+    parser.type_parameters.extend(type_parameters.iter().cloned());
+    type_parameters
+}
+
+fn parse_function_declaration(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    let asynchronous = match parser.peek() {
+        Token::Async => {
+            parser.next();
+            true
+        }
+        _ => false,
+    };
+
+    parser.consume(Token::Func, "expect 'func'");
+    Statement::Function(parse_function(parser, line, asynchronous))
+}
+
+/// Parses a function from its name on, shared by function and method declarations.
+fn parse_function(parser: &mut Parser, line: i32, asynchronous: bool) -> Function {
+    let generator = match parser.peek() {
+        Token::Star => {
+            parser.next();
+            true
+        }
+        _ => false,
+    };
+
+    if generator && asynchronous {
+        parser.error(line, "a generator can't be async".to_string());
+    }
+
+    let name = parser.consume_identifier("expect function name");
+    let scope = parser.type_parameters.len();
+    let type_parameters = parse_type_parameters(parser);
+
+    let mut function = parse_function_rest(parser, name, line, generator, asynchronous);
+    function.type_parameters = type_parameters;
+    parser.type_parameters.truncate(scope);
+    function
+}
+
+/// Parses a function from its parameter list on.
+fn parse_function_rest(parser: &mut Parser, name: String, line: i32, generator: bool, asynchronous: bool) -> Function {
+    parser.consume(Token::LeftParen, "expect '(' after function name");
+    let parameters = parse_parameters(parser);
+
+    let return_type = parse_annotation(parser);
+    let enclosing = (
+        std::mem::replace(&mut parser.generator, generator),
+        std::mem::replace(&mut parser.asynchronous, asynchronous),
+    );
+    let body = parse_function_body(parser);
+    (parser.generator, parser.asynchronous) = enclosing;
+
+    Function {
+        name,
+        type_parameters: Vec::new(),
+        parameters,
+        return_type,
+        generator,
+        asynchronous,
+        body,
+        line,
+    }
+}
+
+/// Parses `operator +(other) { body }` in a class body into a method named
+/// after the operator, `[]` and `[]=` for indexing and `unary-` for negation.
+fn parse_operator(parser: &mut Parser, line: i32) -> Function {
+    parser.next();
+
+    let symbol = match parser.next() {
+        Token::LeftBracket => {
+            parser.consume(Token::RightBracket, "expect ']' after '['");
+            match parser.peek() {
+                Token::Equal => {
+                    parser.next();
+                    "[]=".to_string()
+                }
+                _ => "[]".to_string(),
+            }
+        }
+        token if is_overloadable(token) => token.to_string(),
+        _ => {
+            parser.syntax_error("expect an operator after 'operator'");
+            String::new()
+        }
+    };
+
+    let mut function = parse_function_rest(parser, symbol.clone(), line, false, false);
+
+    let positional = function
+        .parameters
+        .iter()
+        .all(|parameter| parameter.kind == ParameterKind::Positional);
+    let arity = function.parameters.len();
+    let (valid, expected) = match symbol.as_str() {
+        "~" => (arity == 0, "no parameters"),
+        "-" => (arity <= 1, "0 or 1 parameters"),
+        "[]=" => (arity == 2, "2 parameters"),
+        _ => (arity == 1, "1 parameter"),
+    };
+    if !positional || !valid {
+        parser.error(line, format!("operator '{}' must take {}", symbol, expected));
+    }
+
+    if symbol == "-" && arity == 0 {
+        function.name = "unary-".to_string();
+    }
+    function
+}
+
+fn is_overloadable(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Plus
+            | Token::Minus
+            | Token::Star
+            | Token::Slash
+            | Token::Percent
+            | Token::StarStar
+            | Token::Ampersand
+            | Token::Pipe
+            | Token::Caret
+            | Token::Tilde
+            | Token::LessLess
+            | Token::GreaterGreater
+            | Token::GreaterGreaterGreater
+            | Token::Less
+            | Token::LessEqual
+            | Token::Greater
+            | Token::GreaterEqual
+            | Token::EqualEqual
+    )
+}
+
+/// Parses the parameters of a function up to its closing parenthesis, the
+/// optional `[...]`, named `{...}` or rest `...name` ones last.
+fn parse_parameters(parser: &mut Parser) -> Vec<Parameter> {
+    let mut parameters = Vec::new();
+    while !parser.at_end_of(&Token::RightParen) {
+        let (kind, closing) = match parser.peek() {
+            Token::LeftBracket => (ParameterKind::Optional, Token::RightBracket),
+            Token::LeftBrace => (ParameterKind::Named, Token::RightBrace),
+            Token::DotDotDot => {
+                parser.next();
+                parameters.push(parse_parameter(parser, ParameterKind::Rest));
+                parser.consume(Token::RightParen, "a rest parameter must be the last parameter");
+                return parameters;
+            }
+            _ => {
+                parameters.push(parse_parameter(parser, ParameterKind::Positional));
+                if parser.peek() != &Token::RightParen {
+                    parser.consume(Token::Comma, "expect ',' between parameters");
+                }
+                continue;
+            }
+        };
+
+        parser.next();
+        while !parser.at_end_of(&closing) {
+            parameters.push(parse_parameter(parser, kind));
+            if parser.peek() != &closing {
+                parser.consume(Token::Comma, "expect ',' between parameters");
+            }
+        }
+        parser.consume(closing, "expect closing bracket after parameters");
+        parser.consume(Token::RightParen, "optional and named parameters must come last");
+        return parameters;
+    }
+
+    parser.consume(Token::RightParen, "expect ')' after parameters");
+    parameters
+}
+
+fn parse_parameter(parser: &mut Parser, kind: ParameterKind) -> Parameter {
+    let line = parser.line();
+    let kind = match parser.peek() {
+        Token::Identifier(name) if kind == ParameterKind::Named && name == "required" => {
+            parser.next();
+            ParameterKind::Required
+        }
+        _ => kind,
+    };
+
+    let name = parser.consume_identifier("expect parameter name");
+    let annotation = parse_annotation(parser);
+
+    let default = match parser.peek() {
+        Token::Equal => {
+            parser.next();
+            Some(parse(parser))
+        }
+        _ => None,
+    };
+
+    if default.is_some() {
+        match kind {
+            ParameterKind::Positional | ParameterKind::Required => {
+                parser.error(line, format!("required parameter '{}' can't have a default value", name))
+            }
+            ParameterKind::Rest => parser.error(line, format!("rest parameter '{}' can't have a default value", name)),
+            ParameterKind::Optional | ParameterKind::Named => {}
+        }
+    }
+
+    Parameter {
+        name,
+        annotation,
+        kind,
+        default,
+    }
+}
+
+fn parse_class_declaration(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Class, "expect 'class'");
+    let name = parser.consume_identifier("expect class name");
+    let scope = parser.type_parameters.len();
+    let type_parameters = parse_type_parameters(parser);
+
+    let superclass = match parser.peek() {
+        Token::Extends => {
+            parser.next();
+            let name = parser.consume_identifier("expect superclass name after 'extends'");
+            Some(parse_named_type(parser, name))
+        }
+        _ => None,
+    };
+
+    parser.consume(Token::LeftBrace, "expect '{' before class body");
+    let (fields, methods) = parse_members(parser);
+    parser.consume(Token::RightBrace, "expect '}' after class body");
+    parser.type_parameters.truncate(scope);
+
+    Statement::Class(Class {
+        name,
+        type_parameters,
+        superclass,
+        fields,
+        methods,
+        line,
+    })
+}
+
+/// Parses a function body, where the enclosing loops and switches are out of
+/// reach of `break` and `continue`.
+fn parse_function_body(parser: &mut Parser) -> Vec<Statement> {
+    let loops = std::mem::take(&mut parser.loops);
+    let switches = std::mem::replace(&mut parser.switches, 0);
+    parser.functions += 1;
+
+    let body = parse_block(parser);
+
+    parser.functions -= 1;
+    parser.switches = switches;
+    parser.loops = loops;
+    body
+}
+
+/// Parses the fields and methods of a class or enum body up to its closing brace.
+fn parse_members(parser: &mut Parser) -> (Vec<Field>, Vec<Function>) {
+    parser.classes += 1;
+
+    let mut fields = Vec::new();
+    let mut methods = Vec::new();
+    while parser.peek() != &Token::RightBrace && parser.peek() != &Token::Eof {
+        let line = parser.line();
+        match parser.peek() {
+            Token::Var => {
+                parser.next();
+                let name = parser.consume_identifier("expect field name");
+                match parse_var_initializer(parser, Mutability::Var, name, line) {
+                    Statement::Var(_, name, annotation, initializer, line) => fields.push(Field {
+                        name,
+                        annotation,
+                        initializer,
+                        line,
+                    }),
+                    _ => unreachable!(),
+                }
+            }
+            Token::Async => {
+                parser.next();
+                methods.push(parse_function(parser, line, true));
+            }
+            // `operator` only names an operator when no parameter list follows it
+            Token::Identifier(name) if name == "operator" && parser.peek_next() != &Token::LeftParen => {
+                methods.push(parse_operator(parser, line))
+            }
+            Token::Identifier(_) | Token::Star => methods.push(parse_function(parser, line, false)),
+            _ => {
+                parser.syntax_error("expect field or method declaration");
+                parser.next();
+            }
+        }
+    }
+
+    parser.classes -= 1;
+    (fields, methods)
+}
+
+fn parse_extension_declaration(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.next();
+    let name = parser.consume_identifier("expect extension name");
+
+    match parser.peek() {
+        Token::Identifier(on) if on == "on" => {
+            parser.next();
+        }
+        _ => parser.syntax_error("expect 'on' after extension name"),
+    }
+    let on = parse_type(parser);
+
+    parser.consume(Token::LeftBrace, "expect '{' before extension body");
+    let (fields, methods) = parse_members(parser);
+    parser.consume(Token::RightBrace, "expect '}' after extension body");
+
+    if let Some(field) = fields.first() {
+        parser.error(field.line, format!("extension '{}' can't declare field '{}'", name, field.name));
+    }
+
+    Statement::Extension(Extension { name, on, methods, line })
+}
+
+fn parse_enum_declaration(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Enum, "expect 'enum'");
+    let name = parser.consume_identifier("expect enum name");
+    parser.consume(Token::LeftBrace, "expect '{' before enum body");
+
+    let mut values: Vec<EnumValue> = Vec::new();
+    while let Token::Identifier(_) = parser.peek() {
+        let line = parser.line();
+        let name = parser.consume_identifier("expect enum value name");
+        if values.iter().any(|value| value.name == name) {
+            parser.error(line, format!("enum value '{}' is already declared", name));
+        }
+
+        let (arguments, named) = match parser.peek() {
+            Token::LeftParen => parse_arguments(parser),
+            _ => (Vec::new(), Vec::new()),
+        };
+        values.push(EnumValue {
+            name,
+            arguments,
+            named,
+            line,
+        });
+
+        if parser.peek() != &Token::Comma {
+            break;
+        }
+        parser.next();
+    }
+
+    if values.is_empty() {
+        parser.error(line, format!("enum '{}' must declare at least one value", name));
+    }
+
+    // members follow the values after a semicolon
+    let (fields, methods) = match parser.peek() {
+        Token::Semicolon => {
+            parser.next();
+            parse_members(parser)
+        }
+        _ => (Vec::new(), Vec::new()),
+    };
+    parser.consume(Token::RightBrace, "expect '}' after enum body");
+
+    Statement::Enum(Enum {
+        name,
+        values,
+        fields,
+        methods,
+        line,
+    })
+}
+
+fn parse_statement(parser: &mut Parser) -> Statement {
+    match parser.peek() {
+        Token::Print => parse_print_statement(parser),
+        Token::LeftBrace if is_map_assignment(parser) => parse_map_assignment(parser),
+        Token::LeftBrace => Statement::Block(parse_block(parser)),
+        Token::If => parse_if_statement(parser),
+        Token::While => parse_while_statement(parser, None),
+        Token::For => parse_for_statement(parser, None),
+        Token::Break => parse_break_statement(parser),
+        Token::Continue => parse_continue_statement(parser),
+        Token::Switch => parse_switch_statement(parser),
+        Token::Return => parse_return_statement(parser),
+        Token::Yield => parse_yield_statement(parser),
+        Token::Try => parse_try_statement(parser),
+        Token::Assert => parse_assert_statement(parser),
+        Token::Identifier(_) if parser.peek_next() == &Token::Colon => {
+            parse_labeled_statement(parser)
+        }
+        _ => parse_expression_statement(parser),
+    }
+}
+
+fn parse_labeled_statement(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    let label = parser.consume_identifier("expect label");
+    parser.consume(Token::Colon, "expect ':' after label");
+
+    let statement = match parser.peek() {
+        Token::While => parse_while_statement(parser, Some(label.clone())),
+        Token::For => parse_for_statement(parser, Some(label.clone())),
+        _ => {
+            parser.error(line, format!("label '{}' must be followed by a loop", label));
+            parse_statement(parser)
+        }
+    };
+
+    Statement::Labeled(label, Box::new(statement))
+}
+
+fn parse_loop_body(parser: &mut Parser, label: Option<String>) -> Statement {
+    parser.loops.push(label);
+    let body = parse_statement(parser);
+    parser.loops.pop();
+    body
+}
+
+fn parse_break_statement(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Break, "expect 'break'");
+
+    if parser.loops.is_empty() && parser.switches == 0 {
+        parser.error(line, "can't use 'break' outside of a loop or switch".to_string());
+    }
+
+    let label = parse_jump_label(parser, line);
+    parser.consume(Token::Semicolon, "expect ';' after 'break'");
+    Statement::Break(label)
+}
+
+fn parse_continue_statement(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Continue, "expect 'continue'");
+
+    if parser.loops.is_empty() {
+        parser.error(line, "can't use 'continue' outside of a loop".to_string());
+    }
+
+    let label = parse_jump_label(parser, line);
+    parser.consume(Token::Semicolon, "expect ';' after 'continue'");
+    Statement::Continue(label)
+}
+
+/// Parses the optional label of a `break` or `continue` and checks that it
+/// refers to an enclosing loop.
+fn parse_jump_label(parser: &mut Parser, line: i32) -> Option<String> {
+    let label = match parser.peek() {
+        Token::Identifier(label) => {
+            let label = label.clone();
+            parser.next();
+            Some(label)
+        }
+        _ => None,
+    };
+
+    if let Some(label) = &label {
+        if !parser.loops.contains(&Some(label.clone())) {
+            parser.error(line, format!("no enclosing loop labeled '{}'", label));
+        }
+    }
+
+    label
+}
+
+fn parse_switch_value(parser: &mut Parser) -> Expression {
+    parser.consume(Token::Switch, "expect 'switch'");
+    parser.consume(Token::LeftParen, "expect '(' after 'switch'");
+    let value = parse(parser);
+    parser.consume(Token::RightParen, "expect ')' after switch value");
+    value
+}
+
+fn parse_switch_statement(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    let value = parse_switch_value(parser);
+    parser.consume(Token::LeftBrace, "expect '{' before switch cases");
+
+    let mut cases = Vec::new();
+    let mut default = None;
+
+    parser.switches += 1;
+    loop {
+        match parser.peek() {
+            Token::Case => {
+                let line = parser.line();
+                parser.next();
+                let pattern = parse_pattern(parser);
+                check_bindings(parser, line, &pattern);
+                parser.colons += 1;
+                let guard = parse_guard(parser);
+                parser.colons -= 1;
+                parser.consume(Token::Colon, "expect ':' after case pattern");
+                let body = parse_case_body(parser);
+                cases.push(SwitchCase { pattern, guard, body });
+            }
+            Token::Default => {
+                let line = parser.line();
+                parser.next();
+                parser.consume(Token::Colon, "expect ':' after 'default'");
+
+                if default.is_some() {
+                    parser.error(line, "a switch can only have one default case".to_string());
+                }
+                default = Some(parse_case_body(parser));
+            }
+            _ => break,
+        }
+    }
+    parser.switches -= 1;
+
+    parser.consume(Token::RightBrace, "expect '}' after switch cases");
+    Statement::Switch(value, cases, default, line)
+}
+
+fn parse_case_body(parser: &mut Parser) -> Vec<Statement> {
+    let mut statements = Vec::new();
+
+    while !matches!(
+        parser.peek(),
+        Token::Case | Token::Default | Token::RightBrace | Token::Eof
+    ) {
+        statements.push(parse_declaration(parser));
+    }
+
+    statements
+}
+
+fn parse_guard(parser: &mut Parser) -> Option<Expression> {
+    match parser.peek() {
+        Token::When => {
+            parser.next();
+            Some(parse(parser))
+        }
+        _ => None,
+    }
+}
+
+/// Reports the names a pattern binds more than once.
+fn check_bindings(parser: &mut Parser, line: i32, pattern: &Pattern) {
+    let bindings = pattern.bindings();
+    for (index, name) in bindings.iter().enumerate() {
+        if bindings[..index].contains(name) {
+            parser.error(line, format!("'{}' is bound more than once in the pattern", name));
+        }
+    }
+}
+
+fn parse_pattern(parser: &mut Parser) -> Pattern {
+    match parser.peek() {
+        Token::LeftBracket => {
+            parser.next();
+
+            let mut elements = Vec::new();
+            while !parser.at_end_of(&Token::RightBracket) {
+                elements.push(parse_pattern(parser));
+                if parser.peek() != &Token::RightBracket {
+                    parser.consume(Token::Comma, "expect ',' between list pattern elements");
+                }
+            }
+
+            parser.consume(Token::RightBracket, "expect ']' after list pattern");
+            Pattern::List(elements)
+        }
+        Token::LeftBrace => {
+            parser.next();
+
+            let mut entries = Vec::new();
+            while !parser.at_end_of(&Token::RightBrace) {
+                match (parser.peek(), parser.peek_next()) {
+                    (Token::Identifier(_), Token::Colon) => {
+                        let name = parser.consume_identifier("expect map pattern key");
+                        parser.next();
+                        entries.push((Expression::String(name), parse_pattern(parser)));
+                    }
+                    (Token::Identifier(_), _) => {
+                        let name = parser.consume_identifier("expect binding name");
+                        entries.push((Expression::String(name.clone()), Pattern::Binding(name)));
+                    }
+                    _ => {
+                        let key = parse_pattern_constant(parser);
+                        parser.consume(Token::Colon, "expect ':' after map pattern key");
+                        entries.push((key, parse_pattern(parser)));
+                    }
+                }
+
+                if parser.peek() != &Token::RightBrace {
+                    parser.consume(Token::Comma, "expect ',' between map pattern entries");
+                }
+            }
+
+            parser.consume(Token::RightBrace, "expect '}' after map pattern");
+            Pattern::Map(entries)
+        }
+        Token::Identifier(name) if name == "_" => {
+            parser.next();
+            Pattern::Wildcard
+        }
+        Token::Identifier(_) if parser.peek_next() == &Token::LeftParen => {
+            let class = parser.consume_identifier("expect class name");
+            parser.next();
+
+            let mut fields = Vec::new();
+            while !parser.at_end_of(&Token::RightParen) {
+                let field = parser.consume_identifier("expect field name in object pattern");
+                parser.consume(Token::Colon, "expect ':' after field name");
+                fields.push((field, parse_pattern(parser)));
+                if parser.peek() != &Token::RightParen {
+                    parser.consume(Token::Comma, "expect ',' between object pattern fields");
+                }
+            }
+
+            parser.consume(Token::RightParen, "expect ')' after object pattern");
+            Pattern::Object(class, fields)
+        }
+        Token::Identifier(_) if parser.peek_next() == &Token::Dot => {
+            let owner = parser.consume_identifier("expect enum or class name");
+            parser.next();
+            let name = parser.consume_identifier("expect constant name after '.'");
+            Pattern::Constant(Expression::Get(Box::new(Expression::Variable(owner)), name))
+        }
+        Token::Identifier(_) => Pattern::Binding(parser.consume_identifier("expect binding name")),
+        _ => Pattern::Constant(parse_pattern_constant(parser)),
+    }
+}
+
+fn parse_pattern_constant(parser: &mut Parser) -> Expression {
+    match parser.peek() {
+        Token::Number(_)
+        | Token::Integer(_)
+        | Token::String(_)
+        | Token::True
+        | Token::False
+        | Token::Nil
+        | Token::Minus => {
+            parse_expr(parser, Precedence::Unary)
+        }
+        _ => {
+            parser.syntax_error("expect pattern");
+            Expression::Nil
+        }
+    }
+}
+
+fn parse_block(parser: &mut Parser) -> Vec<Statement> {
+    if parser.peek() != &Token::LeftBrace {
+        parser.syntax_error("expect '{' before block");
+        return Vec::new();
+    }
+    parser.next();
+
+    let mut statements = Vec::new();
+    while parser.peek() != &Token::RightBrace && parser.peek() != &Token::Eof {
+        statements.push(parse_declaration(parser));
+    }
+
+    parser.consume(Token::RightBrace, "expect '}' after block");
+    statements
+}
+
+fn parse_try_statement(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Try, "expect 'try'");
+    let body = parse_block(parser);
+
+    let catch = match parser.peek() {
+        Token::Catch => {
+            parser.next();
+            parser.consume(Token::LeftParen, "expect '(' after 'catch'");
+            let exception = parser.consume_identifier("expect exception name");
+            let stack = match parser.peek() {
+                Token::Comma => {
+                    parser.next();
+                    Some(parser.consume_identifier("expect stack trace name"))
+                }
+                _ => None,
+            };
+            parser.consume(Token::RightParen, "expect ')' after catch parameters");
+
+            Some(Catch {
+                exception,
+                stack,
+                body: parse_block(parser),
+            })
+        }
+        _ => None,
+    };
+
+    let finally = match parser.peek() {
+        Token::Finally => {
+            parser.next();
+            Some(parse_block(parser))
+        }
+        _ => None,
+    };
+
+    if catch.is_none() && finally.is_none() {
+        parser.error(line, "a try needs a catch or a finally clause".to_string());
+    }
+
+    Statement::Try(Try { body, catch, finally })
+}
+
+fn parse_assert_statement(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Assert, "expect 'assert'");
+    parser.consume(Token::LeftParen, "expect '(' after 'assert'");
+    let condition = parse(parser);
+
+    let mut message = None;
+    if parser.peek() == &Token::Comma {
+        parser.next();
+        if parser.peek() != &Token::RightParen {
+            message = Some(parse(parser));
+        }
+        if parser.peek() == &Token::Comma {
+            parser.next();
+        }
+    }
+
+    parser.consume(Token::RightParen, "expect ')' after assert arguments");
+    parser.consume(Token::Semicolon, "expect ';' after assert");
+
+    // a disabled assert leaves an empty statement, nothing of it is evaluated
+    if !parser.asserts {
+        return Statement::Block(Vec::new());
     }
+
+    Statement::Assert(condition, message, line)
 }
 
-pub fn parse(parser: &mut Parser) -> Expression {
-    return parse_expr(parser, Precedence::None);
+fn parse_print_statement(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Print, "expect 'print'");
+    let value = parse(parser);
+    parser.consume(Token::Semicolon, "expect ';' after value");
+    Statement::Print(value, line)
+}
+
+fn parse_return_statement(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Return, "expect 'return'");
+
+    if parser.functions == 0 {
+        parser.error(line, "can't return from top-level code".to_string());
+    }
+
+    let value = match parser.peek() {
+        Token::Semicolon => None,
+        _ => Some(parse(parser)),
+    };
+
+    if parser.generator && value.is_some() {
+        parser.error(line, "can't return a value from a generator".to_string());
+    }
+
+    parser.consume(Token::Semicolon, "expect ';' after return value");
+    Statement::Return(value, line)
+}
+
+fn parse_yield_statement(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parser.consume(Token::Yield, "expect 'yield'");
+
+    if !parser.generator {
+        parser.error(line, "can't use 'yield' outside of a generator".to_string());
+    }
+
+    let value = parse(parser);
+    parser.consume(Token::Semicolon, "expect ';' after yielded value");
+    Statement::Yield(value, line)
+}
+
+fn parse_if_statement(parser: &mut Parser) -> Statement {
+    parser.consume(Token::If, "expect 'if'");
+    parser.consume(Token::LeftParen, "expect '(' after 'if'");
+    let condition = parse(parser);
+    parser.consume(Token::RightParen, "expect ')' after if condition");
+
+    let then_branch = parse_statement(parser);
+    let else_branch = match parser.peek() {
+        Token::Else => {
+            parser.next();
+            Some(Box::new(parse_statement(parser)))
+        }
+        _ => None,
+    };
+
+    Statement::If(condition, Box::new(then_branch), else_branch)
+}
+
+fn parse_while_statement(parser: &mut Parser, label: Option<String>) -> Statement {
+    parser.consume(Token::While, "expect 'while'");
+    parser.consume(Token::LeftParen, "expect '(' after 'while'");
+    let condition = parse(parser);
+    parser.consume(Token::RightParen, "expect ')' after while condition");
+
+    let body = parse_loop_body(parser, label);
+    Statement::While(condition, Box::new(body))
+}
+
+fn parse_for_statement(parser: &mut Parser, label: Option<String>) -> Statement {
+    parser.consume(Token::For, "expect 'for'");
+    parser.consume(Token::LeftParen, "expect '(' after 'for'");
+
+    let initializer = match parser.peek() {
+        Token::Semicolon => {
+            parser.next();
+            None
+        }
+        Token::Var => {
+            let line = parser.line();
+            parser.next();
+            let name = parser.consume_identifier("expect variable name");
+
+            if parser.peek() == &Token::In {
+                return parse_for_in_statement(parser, name, label);
+            }
+
+            Some(Box::new(parse_var_initializer(parser, Mutability::Var, name, line)))
+        }
+        _ => Some(Box::new(parse_expression_statement(parser))),
+    };
+
+    let condition = match parser.peek() {
+        Token::Semicolon => None,
+        _ => Some(parse(parser)),
+    };
+    parser.consume(Token::Semicolon, "expect ';' after loop condition");
+
+    let increment = match parser.peek() {
+        Token::RightParen => None,
+        _ => Some(parse(parser)),
+    };
+    parser.consume(Token::RightParen, "expect ')' after for clauses");
+
+    let body = parse_loop_body(parser, label);
+    Statement::For(initializer, condition, increment, Box::new(body))
+}
+
+fn parse_for_in_statement(parser: &mut Parser, name: String, label: Option<String>) -> Statement {
+    parser.consume(Token::In, "expect 'in' after loop variable");
+    let line = parser.line();
+    let iterable = parse(parser);
+    parser.consume(Token::RightParen, "expect ')' after iterable");
+
+    let body = parse_loop_body(parser, label);
+    Statement::ForIn(name, iterable, Box::new(body), line)
 }
 
+fn parse_expression_statement(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    let expression = parse(parser);
+
+    // the trailing semicolon is optional at the end of the input, so the REPL accepts bare expressions
+    if parser.peek() != &Token::Eof {
+        parser.consume(Token::Semicolon, "expect ';' after expression");
+    }
+
+    Statement::Expression(expression, line)
+}
+
+#[allow(clippy::needless_return)]
 fn parse_expr(parser: &mut Parser, precedence: Precedence) -> Expression {
 
     let mut expr: Option<Expression> = None;
@@ -48,29 +1434,45 @@ fn parse_expr(parser: &mut Parser, precedence: Precedence) -> Expression {
         expr = parse_prefix(parser)
     }
 
+    let mut expr = match expr {
+        Some(expr) => expr,
+        // a missing operand reads as `nil` while the parser recovers
+        None => {
+            parser.syntax_error("expect expression");
+            Expression::Nil
+        }
+    };
+
     while parser.peek() != &Token::Eof  {
-        if precedence >= get_precedence(parser.peek()) {
+        if precedence >= peek_precedence(parser, &expr) {
             break;
         }
 
         if is_infix(parser) {
-            expr = Some(parse_infix(parser, expr.unwrap()))
+            expr = parse_infix(parser, expr)
+        } else {
+            break;
         }
     }
 
-    return match expr {
-        Some(expr) => expr,
-        None => panic!("wtf"),
-    };
+    return expr;
 }
 
+#[allow(clippy::match_like_matches_macro)]
 fn is_infix(parser: &mut Parser) -> bool {
     match parser.peek() {
         Token::Plus
         | Token::Minus
         | Token::Slash
         | Token::Star
-        | Token::Bang
+        | Token::Percent
+        | Token::StarStar
+        | Token::Ampersand
+        | Token::Pipe
+        | Token::Caret
+        | Token::LessLess
+        | Token::GreaterGreater
+        | Token::GreaterGreaterGreater
         | Token::BangEqual
         | Token::Equal
         | Token::EqualEqual
@@ -79,7 +1481,26 @@ fn is_infix(parser: &mut Parser) -> bool {
         | Token::Less
         | Token::LessEqual
         | Token::And
-        | Token::Or => true,
+        | Token::Or
+        | Token::DotDot
+        | Token::DotDotEqual
+        | Token::PlusEqual
+        | Token::MinusEqual
+        | Token::StarEqual
+        | Token::SlashEqual
+        | Token::PercentEqual
+        | Token::QuestionQuestionEqual
+        | Token::PlusPlus
+        | Token::MinusMinus
+        | Token::Dot
+        | Token::LeftParen
+        | Token::LeftBracket
+        | Token::QuestionDot
+        | Token::QuestionLeftBracket
+        | Token::QuestionQuestion
+        | Token::Question
+        | Token::Is
+        | Token::As => true,
         _ => false
     }
 }
@@ -90,19 +1511,388 @@ fn parse_infix(parser: &mut Parser, left: Expression) -> Expression {
         | Token::Minus
         | Token::Slash
         | Token::Star
-        | Token::Bang
+        | Token::Percent
+        | Token::Ampersand
+        | Token::Pipe
+        | Token::Caret
+        | Token::LessLess
+        | Token::GreaterGreater
+        | Token::GreaterGreaterGreater
         | Token::BangEqual
         | Token::EqualEqual
         | Token::Greater
         | Token::GreaterEqual
         | Token::Less
         | Token::LessEqual => parse_binary(parser, left),
-        Token::Equal => parse_assignment(parser, left),
-        Token::And | Token::Or => parse_logical(parser, left),
+        Token::StarStar => parse_exponent(parser, left),
+        Token::Equal => parse_assignment(parser, left, Precedence::None),
+        Token::And | Token::Or | Token::QuestionQuestion => parse_logical(parser, left),
+        Token::DotDot if is_cascade(parser, &left) => parse_cascade(parser, left),
+        Token::DotDot | Token::DotDotEqual => {
+            report_ambiguous_range(parser, &left);
+            parse_range(parser, left)
+        }
+        Token::PlusEqual
+        | Token::MinusEqual
+        | Token::StarEqual
+        | Token::SlashEqual
+        | Token::PercentEqual
+        | Token::QuestionQuestionEqual => parse_compound_assignment(parser, left, Precedence::None),
+        Token::PlusPlus | Token::MinusMinus => parse_postfix(parser, left),
+        Token::Dot => parse_get(parser, left),
+        Token::LeftParen => parse_call(parser, left),
+        Token::LeftBracket => parse_index(parser, left),
+        Token::QuestionLeftBracket if is_conditional_index(parser) => parse_conditional_index(parser, left),
+        Token::QuestionDot | Token::QuestionLeftBracket => parse_null_aware_chain(parser, left),
+        Token::Question => parse_ternary(parser, left),
+        Token::Is | Token::As => parse_type_check(parser, left),
         _ => panic!("unknow infix token")
     }
 }
 
+/// `..` starts a cascade section when it is followed by a name, optionally
+/// continued by `.name` accesses, and then by an assignment, as in
+/// `..address.city = "Paris"`, or by a call, as in `..add(1)`. Anything else is
+/// a range, so `0..n` and `0..xs.length` keep their meaning.
+///
+/// A range may also end with a call, `lo..max(a, b)`, so a call section only
+/// starts a cascade when the receiver can't start a range, as a string, a list
+/// or a parenthesized receiver can't, or when another section is next to it:
+/// `builder..add(1)..build()`. Otherwise `..` reads as a range and
+/// `report_ambiguous_range` asks for parentheses.
+fn is_cascade(parser: &Parser, receiver: &Expression) -> bool {
+    if parser.peek() != &Token::DotDot || is_range_bound(receiver) {
+        return false;
+    }
+
+    match cascade_section_end(parser) {
+        Some(offset) if parser.peek_at(offset) == &Token::LeftParen => {
+            parser.cascades > 0
+                || is_cascade_receiver(receiver)
+                || parser.peek_at(skip_parentheses(parser, offset)) == &Token::DotDot
+        }
+        Some(_) => true,
+        None => false,
+    }
+}
+
+/// Offset of the token following the name chain of a cascade section, when
+/// that token is a call or an assignment.
+fn cascade_section_end(parser: &Parser) -> Option<usize> {
+    let mut offset = 1;
+    if !matches!(parser.peek_at(offset), Token::Identifier(_)) {
+        return None;
+    }
+    offset += 1;
+
+    while parser.peek_at(offset) == &Token::Dot && matches!(parser.peek_at(offset + 1), Token::Identifier(_)) {
+        offset += 2;
+    }
+
+    match parser.peek_at(offset) {
+        Token::LeftParen
+        | Token::Equal
+        | Token::PlusEqual
+        | Token::MinusEqual
+        | Token::StarEqual
+        | Token::SlashEqual
+        | Token::PercentEqual
+        | Token::QuestionQuestionEqual => Some(offset),
+        _ => None,
+    }
+}
+
+/// Offset of the token following the parenthesized list opening at `offset`.
+fn skip_parentheses(parser: &Parser, mut offset: usize) -> usize {
+    let mut depth = 0;
+    loop {
+        match parser.peek_at(offset) {
+            Token::LeftParen => depth += 1,
+            Token::RightParen if depth == 1 => return offset + 1,
+            Token::RightParen => depth -= 1,
+            Token::Eof => return offset,
+            _ => {}
+        }
+        offset += 1;
+    }
+}
+
+/// Whether the expression can't be the start of a range.
+fn is_cascade_receiver(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::String(_) | Expression::List(_) | Expression::Record(..) | Expression::Grouping(_)
+    )
+}
+
+/// Reports `start..name(...)`, which reads as a range but could have been meant
+/// as a cascade.
+fn report_ambiguous_range(parser: &mut Parser, start: &Expression) {
+    if parser.peek() != &Token::DotDot || is_range_bound(start) {
+        return;
+    }
+
+    if let Some(offset) = cascade_section_end(parser) {
+        if parser.peek_at(offset) == &Token::LeftParen {
+            let line = parser.line();
+            parser.error(
+                line,
+                "ambiguous '..', write 'start..(end)' for a range or '(receiver)..method()' for a cascade".to_string(),
+            );
+        }
+    }
+}
+
+/// Whether the expression is a number literal or a range, the start of a range
+/// rather than the receiver of a cascade.
+fn is_range_bound(expression: &Expression) -> bool {
+    match expression {
+        Expression::Number(_) | Expression::Integer(_) | Expression::Range(..) => true,
+        Expression::Unary(UnaryOperator::Minus, operand) | Expression::Grouping(operand) => is_range_bound(operand),
+        _ => false,
+    }
+}
+
+fn peek_precedence(parser: &Parser, left: &Expression) -> Precedence {
+    if is_cascade(parser, left) {
+        return Precedence::Cascade;
+    }
+    if is_conditional_index(parser) {
+        return Precedence::Ternary;
+    }
+    get_precedence(parser.peek())
+}
+
+fn parse_cascade(parser: &mut Parser, receiver: Expression) -> Expression {
+    let mut sections = Vec::new();
+
+    while parser.peek() == &Token::DotDot {
+        parser.next();
+        let name = parser.consume_identifier("expect method or property name after '..'");
+        let mut section = Expression::Get(Box::new(Expression::CascadeReceiver), name);
+
+        while get_precedence(parser.peek()) == Precedence::Call && is_infix(parser) && !is_conditional_index(parser) {
+            section = parse_infix(parser, section);
+        }
+
+        // the assigned value ends at the next section
+        parser.cascades += 1;
+        section = match parser.peek() {
+            Token::Equal => parse_assignment(parser, section, Precedence::Cascade),
+            Token::PlusEqual
+            | Token::MinusEqual
+            | Token::StarEqual
+            | Token::SlashEqual
+            | Token::PercentEqual
+            | Token::QuestionQuestionEqual => {
+                parse_compound_assignment(parser, section, Precedence::Cascade)
+            }
+            _ => section,
+        };
+        parser.cascades -= 1;
+
+        sections.push(section);
+    }
+
+    Expression::Cascade(Box::new(receiver), sections)
+}
+
+fn parse_type_check(parser: &mut Parser, left: Expression) -> Expression {
+    let operator = match parser.next() {
+        Token::As => TypeOperator::As,
+        _ => match parser.peek() {
+            Token::Bang => {
+                parser.next();
+                TypeOperator::IsNot
+            }
+            _ => TypeOperator::Is,
+        },
+    };
+
+    // a trailing `?` is left to the conditional operator, `value is int ? a : b`
+    let name = parser.consume_identifier(&format!("expect type name after '{}'", operator));
+    let target = parse_named_type(parser, name);
+    Expression::TypeCheck(Box::new(left), operator, target)
+}
+
+fn parse_ternary(parser: &mut Parser, condition: Expression) -> Expression {
+    parser.consume(Token::Question, "expect '?'");
+    parser.colons += 1;
+    let then_branch = parse(parser);
+    parser.colons -= 1;
+    parser.consume(Token::Colon, "expect ':' after the first branch of a conditional");
+    // parsing the second branch one level lower makes the operator right associative
+    let else_branch = parse_expr(parser, Precedence::Assign);
+    Expression::Ternary(Box::new(condition), Box::new(then_branch), Box::new(else_branch))
+}
+
+/// The scanner reads `?[` as one token, but `a ?[0] : 1` is a conditional whose
+/// first branch is a list. `?[` starts a conditional when its matching `]` is
+/// followed by a `:` that no enclosing conditional or case guard is waiting for.
+fn is_conditional_index(parser: &Parser) -> bool {
+    if parser.peek() != &Token::QuestionLeftBracket || parser.colons > 0 {
+        return false;
+    }
+
+    let mut depth = 0;
+    let mut offset = 1;
+    loop {
+        match parser.peek_at(offset) {
+            Token::LeftParen | Token::LeftBracket | Token::LeftBrace | Token::QuestionLeftBracket => depth += 1,
+            Token::RightBracket if depth == 0 => return parser.peek_at(offset + 1) == &Token::Colon,
+            Token::RightParen | Token::RightBracket | Token::RightBrace if depth > 0 => depth -= 1,
+            Token::RightParen | Token::RightBrace | Token::Semicolon | Token::Eof => return false,
+            _ => {}
+        }
+        offset += 1;
+    }
+}
+
+/// Splits `?[` into `?` and `[` and parses the conditional they start.
+fn parse_conditional_index(parser: &mut Parser, condition: Expression) -> Expression {
+    let line = parser.line();
+    parser.tokens[parser.position] = Token::Question;
+    parser.tokens.insert(parser.position + 1, Token::LeftBracket);
+    parser.lines.insert(parser.position + 1, line);
+    parse_ternary(parser, condition)
+}
+
+fn parse_null_aware_chain(parser: &mut Parser, left: Expression) -> Expression {
+    let mut chain = parse_null_aware_access(parser, left);
+
+    // the rest of the member chain belongs to the same short-circuit
+    while matches!(
+        parser.peek(),
+        Token::Dot | Token::LeftParen | Token::LeftBracket | Token::QuestionDot | Token::QuestionLeftBracket
+    ) && !is_conditional_index(parser)
+    {
+        chain = match parser.peek() {
+            Token::QuestionDot | Token::QuestionLeftBracket => parse_null_aware_access(parser, chain),
+            _ => parse_infix(parser, chain),
+        };
+    }
+
+    Expression::NullAwareChain(Box::new(chain))
+}
+
+fn parse_null_aware_access(parser: &mut Parser, left: Expression) -> Expression {
+    match parser.next() {
+        Token::QuestionDot => {
+            let name = parser.consume_property_name("expect property name after '?.'");
+            Expression::NullAwareGet(Box::new(left), name)
+        }
+        Token::QuestionLeftBracket => {
+            let index = parse(parser);
+            parser.consume(Token::RightBracket, "expect ']' after index");
+            Expression::NullAwareIndex(Box::new(left), Box::new(index))
+        }
+        _ => unreachable!("a null-aware access is only parsed at '?.' or '?['"),
+    }
+}
+
+fn parse_get(parser: &mut Parser, left: Expression) -> Expression {
+    parser.consume(Token::Dot, "expect '.'");
+    let name = parser.consume_property_name("expect property name after '.'");
+    Expression::Get(Box::new(left), name)
+}
+
+fn parse_call(parser: &mut Parser, left: Expression) -> Expression {
+    let (arguments, named) = parse_arguments(parser);
+    Expression::Call(Box::new(left), arguments, named)
+}
+
+/// Parses the positional, `...spread` and `name: value` arguments of a call.
+fn parse_arguments(parser: &mut Parser) -> (Vec<Expression>, Vec<(String, Expression)>) {
+    parser.consume(Token::LeftParen, "expect '('");
+
+    let mut arguments = Vec::new();
+    let mut named: Vec<(String, Expression)> = Vec::new();
+    while !parser.at_end_of(&Token::RightParen) {
+        match (parser.peek(), parser.peek_next()) {
+            (Token::Identifier(_), Token::Colon) => {
+                let line = parser.line();
+                let name = parser.consume_identifier("expect argument name");
+                parser.next();
+
+                if named.iter().any(|(other, _)| *other == name) {
+                    parser.error(line, format!("argument '{}' is already passed", name));
+                }
+                named.push((name, parse(parser)));
+            }
+            (Token::DotDotDot, _) => {
+                parser.next();
+                arguments.push(Expression::Spread(Box::new(parse(parser))));
+            }
+            _ => arguments.push(parse(parser)),
+        }
+
+        if parser.peek() != &Token::RightParen {
+            parser.consume(Token::Comma, "expect ',' between arguments");
+        }
+    }
+
+    parser.consume(Token::RightParen, "expect ')' after arguments");
+    (arguments, named)
+}
+
+fn parse_index(parser: &mut Parser, left: Expression) -> Expression {
+    parser.consume(Token::LeftBracket, "expect '['");
+    let index = parse(parser);
+    parser.consume(Token::RightBracket, "expect ']' after index");
+    Expression::Index(Box::new(left), Box::new(index))
+}
+
+fn parse_compound_assignment(parser: &mut Parser, left: Expression, precedence: Precedence) -> Expression {
+    let line = parser.line();
+    let operator = get_assign_operator(parser);
+    let right = parse_expr(parser, precedence);
+
+    if !is_assignment_target(&left) {
+        parser.error(line, format!("invalid target for '{}'", operator));
+        return right;
+    }
+
+    Expression::CompoundAssign(Box::new(left), operator, Box::new(right))
+}
+
+fn parse_postfix(parser: &mut Parser, left: Expression) -> Expression {
+    let line = parser.line();
+    let operator = get_postfix_operator(parser);
+
+    if !is_assignment_target(&left) {
+        parser.error(line, format!("invalid target for '{}'", operator));
+        return left;
+    }
+
+    Expression::Postfix(Box::new(left), operator)
+}
+
+fn parse_prefix_update(parser: &mut Parser) -> Expression {
+    let line = parser.line();
+    let token = parser.next().clone();
+    let operator = match token {
+        Token::PlusPlus => AssignOperator::Plus,
+        Token::MinusMinus => AssignOperator::Minus,
+        _ => unreachable!("parse_prefix is only called with '++' or '--' next"),
+    };
+    let target = parse_expr(parser, Precedence::Unary);
+
+    if !is_assignment_target(&target) {
+        parser.error(line, format!("invalid target for '{}'", token));
+        return target;
+    }
+
+    Expression::CompoundAssign(Box::new(target), operator, Box::new(Expression::Integer(1)))
+}
+
+fn is_assignment_target(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::Variable(_) | Expression::Get(_, _) | Expression::Index(_, _)
+    )
+}
+
+#[allow(clippy::needless_return)]
 fn parse_logical(parser: &mut Parser, left: Expression) -> Expression {
     let precedence = get_precedence(parser.peek());
     let operator = get_logical_operator(parser);
@@ -110,29 +1900,111 @@ fn parse_logical(parser: &mut Parser, left: Expression) -> Expression {
     return Expression::Logical(Box::new(left), operator, Box::new(right));
 }
 
-fn parse_assignment(parser: &mut Parser, left: Expression) -> Expression {
+fn parse_range(parser: &mut Parser, left: Expression) -> Expression {
+    let precedence = get_precedence(parser.peek());
+    let operator = get_range_operator(parser);
+    let right = parse_expr(parser, precedence);
+    Expression::Range(Box::new(left), operator, Box::new(right))
+}
+
+fn parse_assignment(parser: &mut Parser, left: Expression, precedence: Precedence) -> Expression {
+    let line = parser.line();
 
     if parser.next() != &Token::Equal {
         panic!("expect equal for assignment")
     }
 
-    let right = parse_expr(parser, Precedence::None);
+    let right = parse_expr(parser, precedence);
     match left {
         Expression::Variable(identifier) => Expression::Assign(identifier, Box::new(right)),
-        _ => panic!("unknow type a assignment")
+        Expression::Get(object, name) => Expression::Set(object, name, Box::new(right)),
+        Expression::Index(object, index) => Expression::SetIndex(object, index, Box::new(right)),
+        Expression::List(_) => match assignment_pattern(&left) {
+            Some(pattern) => {
+                check_bindings(parser, line, &pattern);
+                Expression::Destructure(Box::new(pattern), Box::new(right))
+            }
+            None => {
+                parser.error(line, "invalid destructuring assignment target".to_string());
+                right
+            }
+        },
+        _ => {
+            parser.error(line, "invalid assignment target".to_string());
+            right
+        }
+    }
+}
+
+/// Whether the statement is `{a, b} = value;`, a block can't be followed by `=`.
+fn is_map_assignment(parser: &Parser) -> bool {
+    let mut depth = 0;
+    let mut offset = 0;
+    loop {
+        match parser.peek_at(offset) {
+            Token::LeftBrace => depth += 1,
+            Token::RightBrace if depth == 1 => return parser.peek_at(offset + 1) == &Token::Equal,
+            Token::RightBrace => depth -= 1,
+            Token::Eof => return false,
+            _ => {}
+        }
+        offset += 1;
+    }
+}
+
+/// A map pattern can't be told from a block at the start of a statement, so
+/// only declarations destructure maps. The value is still parsed.
+fn parse_map_assignment(parser: &mut Parser) -> Statement {
+    let line = parser.line();
+    parse_pattern(parser);
+    parser.error(
+        line,
+        "can't assign to a map pattern, destructure the map in a declaration: var {...} = value;".to_string(),
+    );
+    parser.next();
+
+    let value = parse(parser);
+    parser.consume(Token::Semicolon, "expect ';' after expression");
+    Statement::Expression(value, line)
+}
+
+/// Reinterprets the left side of an assignment, already parsed as an
+/// expression, as the pattern it spells.
+fn assignment_pattern(target: &Expression) -> Option<Pattern> {
+    match target {
+        Expression::Variable(name) if name == "_" => Some(Pattern::Wildcard),
+        Expression::Variable(name) => Some(Pattern::Binding(name.clone())),
+        Expression::List(elements) => elements
+            .iter()
+            .map(assignment_pattern)
+            .collect::<Option<Vec<Pattern>>>()
+            .map(Pattern::List),
+        _ => None,
     }
 }
 
+#[allow(clippy::match_like_matches_macro)]
 fn is_prefix(parser: &mut Parser) -> bool {
     match parser.peek() {
         Token::String(_)
         | Token::Number(_)
+        | Token::Integer(_)
         | Token::True
         | Token::False  
+        | Token::Nil
         | Token::Minus
         | Token::Bang
+        | Token::Tilde
         | Token::Identifier(_)
-        | Token::LeftParen => true,
+        | Token::This
+        | Token::Super
+        | Token::LeftParen
+        | Token::LeftBracket
+        | Token::Switch
+        | Token::Throw
+        | Token::Await
+        | Token::PlusPlus
+        | Token::MinusMinus => true,
         _ => false,    
     }
 }
@@ -141,34 +2013,167 @@ fn parse_prefix(parser: &mut Parser) -> Option<Expression> {
     match parser.peek() {
         Token::String(_)
         | Token::Number(_)
+        | Token::Integer(_)
         | Token::True
         | Token::Identifier(_)
+        | Token::Nil
         | Token::False => Some(parse_primary(parser)),
-        | Token::Minus | Token::Bang => Some(parse_unary(parser)),
+        | Token::Minus | Token::Bang | Token::Tilde => Some(parse_unary(parser)),
+        | Token::PlusPlus | Token::MinusMinus => Some(parse_prefix_update(parser)),
+        | Token::This => Some(parse_this(parser)),
+        | Token::Super => Some(parse_super(parser)),
         | Token::LeftParen => Some(parse_grouping(parser)),
+        | Token::LeftBracket => Some(parse_list(parser)),
+        | Token::Switch => Some(parse_switch_expression(parser)),
+        | Token::Throw => Some(parse_throw(parser)),
+        | Token::Await => Some(parse_await(parser)),
         _ => None,
     }
 }
 
+fn parse_this(parser: &mut Parser) -> Expression {
+    let line = parser.line();
+    parser.consume(Token::This, "expect 'this'");
+
+    if parser.classes == 0 {
+        parser.error(line, "can't use 'this' outside of a class".to_string());
+    }
+
+    Expression::This
+}
+
+fn parse_super(parser: &mut Parser) -> Expression {
+    let line = parser.line();
+    parser.consume(Token::Super, "expect 'super'");
+
+    if parser.classes == 0 {
+        parser.error(line, "can't use 'super' outside of a class".to_string());
+    }
+    if parser.peek() != &Token::Dot {
+        parser.syntax_error("expect '.' after 'super'");
+    }
+
+    Expression::Super
+}
+
+/// Parses a parenthesized expression, which is a record when it is empty,
+/// starts with a named field or holds a comma, as in `(1,)`.
 fn parse_grouping(parser: &mut Parser) -> Expression {
-    let grouping = match parser.next() {
-        Token::LeftParen => {
-            let expr = parse_expr(parser, Precedence::None);
-            if parser.next().clone() != Token::RightParen {
-                panic!()
+    parser.consume(Token::LeftParen, "expect '('");
+
+    match (parser.peek(), parser.peek_next()) {
+        (Token::RightParen, _) | (Token::Identifier(_), Token::Colon) => return parse_record(parser, Vec::new()),
+        _ => {}
+    }
+
+    let expr = parse_expr(parser, Precedence::None);
+    match parser.peek() {
+        Token::Comma => {
+            parser.next();
+            parse_record(parser, vec![expr])
+        }
+        _ => {
+            parser.consume(Token::RightParen, "expect ')' after expression");
+            Expression::Grouping(Box::new(expr))
+        }
+    }
+}
+
+/// Parses the rest of a record, after its opening parenthesis and the fields already read.
+fn parse_record(parser: &mut Parser, mut positional: Vec<Expression>) -> Expression {
+    let mut named: Vec<(String, Expression)> = Vec::new();
+
+    while !parser.at_end_of(&Token::RightParen) {
+        match (parser.peek(), parser.peek_next()) {
+            (Token::Identifier(_), Token::Colon) => {
+                let line = parser.line();
+                let name = parser.consume_identifier("expect field name");
+                parser.next();
+
+                if named.iter().any(|(other, _)| *other == name) {
+                    parser.error(line, format!("record field '{}' is already declared", name));
+                }
+                named.push((name, parse(parser)));
             }
-            expr
+            _ => positional.push(parse(parser)),
         }
-        _ => panic!(""),
-    };
 
-    return Expression::Grouping(Box::new(grouping));
+        if parser.peek() != &Token::RightParen {
+            parser.consume(Token::Comma, "expect ',' between record fields");
+        }
+    }
+
+    parser.consume(Token::RightParen, "expect ')' after record fields");
+    Expression::Record(positional, named)
+}
+
+fn parse_list(parser: &mut Parser) -> Expression {
+    parser.consume(Token::LeftBracket, "expect '['");
+
+    let mut elements = Vec::new();
+    while !parser.at_end_of(&Token::RightBracket) {
+        elements.push(parse(parser));
+        if parser.peek() != &Token::RightBracket {
+            parser.consume(Token::Comma, "expect ',' between list elements");
+        }
+    }
+
+    parser.consume(Token::RightBracket, "expect ']' after list elements");
+    Expression::List(elements)
+}
+
+fn parse_switch_expression(parser: &mut Parser) -> Expression {
+    let value = parse_switch_value(parser);
+    parser.consume(Token::LeftBrace, "expect '{' before switch arms");
+
+    let mut arms = Vec::new();
+    while !parser.at_end_of(&Token::RightBrace) {
+        let line = parser.line();
+        let pattern = parse_pattern(parser);
+        check_bindings(parser, line, &pattern);
+        let guard = parse_guard(parser);
+        parser.consume(Token::EqualGreater, "expect '=>' after switch arm pattern");
+        let body = parse(parser);
+        arms.push(SwitchArm { pattern, guard, body });
+
+        if parser.peek() != &Token::RightBrace {
+            parser.consume(Token::Comma, "expect ',' between switch arms");
+        }
+    }
+
+    parser.consume(Token::RightBrace, "expect '}' after switch arms");
+    Expression::Switch(Box::new(value), arms)
+}
+
+fn parse_throw(parser: &mut Parser) -> Expression {
+    parser.consume(Token::Throw, "expect 'throw'");
+    let value = parse(parser);
+    Expression::Throw(Box::new(value))
+}
+
+fn parse_await(parser: &mut Parser) -> Expression {
+    let line = parser.line();
+    parser.consume(Token::Await, "expect 'await'");
+
+    if !parser.asynchronous {
+        parser.error(line, "can't use 'await' outside of an async function".to_string());
+    }
+
+    let value = parse_expr(parser, Precedence::Unary);
+    Expression::Await(Box::new(value))
 }
 
 fn parse_binary(parser: &mut Parser, left: Expression) -> Expression {
     let precedence = get_precedence(parser.peek());
     let operator = get_binary_operator(parser);
-    let right = parse_expr(parser, precedence);
+    let right = parse_expr(parser, precedence);
+    Expression::Binary(Box::new(left), operator, Box::new(right))
+}
+
+fn parse_exponent(parser: &mut Parser, left: Expression) -> Expression {
+    let operator = get_binary_operator(parser);
+    // parsing the right operand one level lower makes `**` right associative
+    let right = parse_expr(parser, Precedence::Unary);
     Expression::Binary(Box::new(left), operator, Box::new(right))
 }
 
@@ -178,17 +2183,21 @@ fn parse_unary(parser: &mut Parser) -> Expression {
     Expression::Unary(operator, Box::new(right))
 }
 
+#[allow(clippy::needless_return, clippy::clone_on_copy)]
 fn parse_primary(parser: &mut Parser) -> Expression {
     return match parser.next() {
         Token::Number(number) => Expression::Number(number.clone()),
+        Token::Integer(integer) => Expression::Integer(*integer),
         Token::String(string) => Expression::String(string.clone()),
         Token::True => Expression::Boolean(true),
         Token::False => Expression::Boolean(false),
+        Token::Nil => Expression::Nil,
         Token::Identifier(identifier) => Expression::Variable(identifier.clone()),
         _ => panic!(),
     };
 }
 
+#[allow(clippy::needless_return)]
 fn get_precedence(token: &Token) -> Precedence {
     return match token {
         Token::Equal => Precedence::Assign,
@@ -200,38 +2209,102 @@ fn get_precedence(token: &Token) -> Precedence {
         Token::LessEqual => Precedence::Comparison,
         Token::Greater => Precedence::Comparison,
         Token::GreaterEqual => Precedence::Comparison,
+        Token::Is => Precedence::Comparison,
+        Token::As => Precedence::Comparison,
+
+        Token::DotDot => Precedence::Range,
+        Token::DotDotEqual => Precedence::Range,
+
+        Token::Pipe => Precedence::BitwiseOr,
+        Token::Caret => Precedence::BitwiseXor,
+        Token::Ampersand => Precedence::BitwiseAnd,
+
+        Token::LessLess => Precedence::Shift,
+        Token::GreaterGreater => Precedence::Shift,
+        Token::GreaterGreaterGreater => Precedence::Shift,
 
         Token::Minus => Precedence::Term,
         Token::Plus => Precedence::Term,
 
         Token::Star => Precedence::Factor,
         Token::Slash => Precedence::Factor,
+        Token::Percent => Precedence::Factor,
+
+        Token::StarStar => Precedence::Exponent,
+
+        Token::PlusEqual => Precedence::Assign,
+        Token::MinusEqual => Precedence::Assign,
+        Token::StarEqual => Precedence::Assign,
+        Token::SlashEqual => Precedence::Assign,
+        Token::PercentEqual => Precedence::Assign,
+        Token::QuestionQuestionEqual => Precedence::Assign,
 
         Token::Bang => Precedence::Unary,
         Token::LeftParen => Precedence::Call,
+        Token::LeftBracket => Precedence::Call,
+        Token::Dot => Precedence::Call,
+        Token::QuestionDot => Precedence::Call,
+        Token::QuestionLeftBracket => Precedence::Call,
+        Token::PlusPlus => Precedence::Call,
+        Token::MinusMinus => Precedence::Call,
 
         Token::And => Precedence::And,
         Token::Or => Precedence::Or,
+        Token::QuestionQuestion => Precedence::IfNull,
+        Token::Question => Precedence::Ternary,
         _ => Precedence::None,
     };
 }
 
+#[allow(clippy::needless_return)]
 fn get_logical_operator(parser: &mut Parser) -> LogicalOperator {
     return match parser.next() {
         Token::And => LogicalOperator::And,
         Token::Or => LogicalOperator::Or,
+        Token::QuestionQuestion => LogicalOperator::QuestionQuestion,
         _ => todo!(),
     }
 }
 
+fn get_assign_operator(parser: &mut Parser) -> AssignOperator {
+    match parser.next() {
+        Token::PlusEqual => AssignOperator::Plus,
+        Token::MinusEqual => AssignOperator::Minus,
+        Token::StarEqual => AssignOperator::Star,
+        Token::SlashEqual => AssignOperator::Slash,
+        Token::PercentEqual => AssignOperator::Percent,
+        Token::QuestionQuestionEqual => AssignOperator::QuestionQuestion,
+        _ => unreachable!("parse_infix only parses a compound assignment at a compound assignment token"),
+    }
+}
+
+fn get_postfix_operator(parser: &mut Parser) -> PostfixOperator {
+    match parser.next() {
+        Token::PlusPlus => PostfixOperator::PlusPlus,
+        Token::MinusMinus => PostfixOperator::MinusMinus,
+        _ => unreachable!("parse_infix only parses a postfix update at '++' or '--'"),
+    }
+}
+
+fn get_range_operator(parser: &mut Parser) -> RangeOperator {
+    match parser.next() {
+        Token::DotDot => RangeOperator::DotDot,
+        Token::DotDotEqual => RangeOperator::DotDotEqual,
+        _ => unreachable!("parse_infix only parses a range at '..' or '..='"),
+    }
+}
+
+#[allow(clippy::needless_return)]
 fn get_unary_operator(parser: &mut Parser) -> UnaryOperator {
     return match parser.next() {
         Token::Minus => UnaryOperator::Minus,
         Token::Bang => UnaryOperator::Bang,
+        Token::Tilde => UnaryOperator::Tilde,
         _ => todo!()
     }
 }
 
+#[allow(clippy::needless_return)]
 fn get_binary_operator(parser: &mut Parser) -> BinaryOperator {
     return match parser.next() {
         Token::BangEqual => BinaryOperator::BangEqual,
@@ -243,6 +2316,14 @@ fn get_binary_operator(parser: &mut Parser) -> BinaryOperator {
         Token::Plus => BinaryOperator::Plus,
         Token::Star => BinaryOperator::Star,
         Token::Slash => BinaryOperator::Slash,
+        Token::Percent => BinaryOperator::Percent,
+        Token::StarStar => BinaryOperator::StarStar,
+        Token::Ampersand => BinaryOperator::Ampersand,
+        Token::Pipe => BinaryOperator::Pipe,
+        Token::Caret => BinaryOperator::Caret,
+        Token::LessLess => BinaryOperator::LessLess,
+        Token::GreaterGreater => BinaryOperator::GreaterGreater,
+        Token::GreaterGreaterGreater => BinaryOperator::GreaterGreaterGreater,
         Token::EqualEqual => BinaryOperator::EqualEqual,
         _ => todo!(),
     };
@@ -250,7 +2331,34 @@ fn get_binary_operator(parser: &mut Parser) -> BinaryOperator {
 
 #[cfg(test)]
 mod tests {
-    use super::{Parser, Token, parse};
+    use super::{Parser, Token, parse, parse_program};
+    use crate::{
+        diagnostic::Diagnostic,
+        expression::{
+            AssignOperator, BinaryOperator, Expression, LogicalOperator, PostfixOperator,
+            RangeOperator, SwitchArm, TypeOperator, UnaryOperator,
+        },
+        pattern::Pattern,
+        scanner::Scanner,
+        statement::{
+            Catch, Class, Enum, EnumValue, Extension, Field, Function, Import, Mutability, Parameter, ParameterKind, Statement,
+            SwitchCase, Try,
+        },
+        types::Type,
+    };
+
+    fn parse_source(source: &str) -> Vec<Statement> {
+        let mut scanner = Scanner::new(source.to_string());
+        let mut parser = Parser::new(scanner.scan().clone());
+        parse_program(&mut parser)
+    }
+
+    fn parse_diagnostics(source: &str) -> Vec<Diagnostic> {
+        let mut scanner = Scanner::new(source.to_string());
+        let mut parser = Parser::new(scanner.scan().clone());
+        parse_program(&mut parser);
+        parser.diagnostics
+    }
 
     #[test]
     pub fn test_parse_grouping() {
@@ -258,15 +2366,1442 @@ mod tests {
             Token::LeftParen,
             Token::Number(10.3),
             Token::Plus,
-            Token::Number(23.0),
+            Token::Integer(23),
             Token::RightParen,
         ];
 
-        let mut parser = Parser {
-            tokens: tokens.clone(),
-            position: 0
-        };
+        let mut parser = Parser::new(tokens);
 
         let expr = parse(&mut parser);
+
+        assert_eq!(
+            expr,
+            Expression::Grouping(Box::new(Expression::Binary(
+                Box::new(Expression::Number(10.3)),
+                BinaryOperator::Plus,
+                Box::new(Expression::Integer(23)),
+            )))
+        );
+    }
+
+    #[test]
+    pub fn test_parse_range() {
+        let statements = parse_source("0..n + 1;");
+
+        assert_eq!(
+            statements,
+            vec![Statement::Expression(Expression::Range(
+                Box::new(Expression::Integer(0)),
+                RangeOperator::DotDot,
+                Box::new(Expression::Binary(
+                    Box::new(Expression::Variable("n".to_string())),
+                    BinaryOperator::Plus,
+                    Box::new(Expression::Integer(1)),
+                )),
+            ), 1)]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_for_in() {
+        let statements = parse_source("for (var x in 1..=3) {\n  print x;\n}");
+
+        assert_eq!(
+            statements,
+            vec![Statement::ForIn(
+                "x".to_string(),
+                Expression::Range(
+                    Box::new(Expression::Integer(1)),
+                    RangeOperator::DotDotEqual,
+                    Box::new(Expression::Integer(3)),
+                ),
+                Box::new(Statement::Block(vec![Statement::Print(Expression::Variable(
+                    "x".to_string()
+                ), 2)])),
+                1,
+            )]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_for() {
+        let statements = parse_source("for (var i = 0; i < 3;) print i;");
+
+        assert_eq!(
+            statements,
+            vec![Statement::For(
+                Some(Box::new(Statement::Var(
+                    Mutability::Var,
+                    "i".to_string(),
+                    None,
+                    Some(Expression::Integer(0)),
+                    1,
+                ))),
+                Some(Expression::Binary(
+                    Box::new(Expression::Variable("i".to_string())),
+                    BinaryOperator::Less,
+                    Box::new(Expression::Integer(3)),
+                )),
+                None,
+                Box::new(Statement::Print(Expression::Variable("i".to_string()), 1)),
+            )]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_labeled_break() {
+        let statements = parse_source("outer: while (true) { for (var x in xs) { continue; break outer; } }");
+
+        assert_eq!(
+            statements,
+            vec![Statement::Labeled(
+                "outer".to_string(),
+                Box::new(Statement::While(
+                    Expression::Boolean(true),
+                    Box::new(Statement::Block(vec![Statement::ForIn(
+                        "x".to_string(),
+                        Expression::Variable("xs".to_string()),
+                        Box::new(Statement::Block(vec![
+                            Statement::Continue(None),
+                            Statement::Break(Some("outer".to_string())),
+                        ])),
+                        1,
+                    )])),
+                )),
+            )]
+        );
+    }
+
+    #[test]
+    pub fn test_break_outside_loop() {
+        let diagnostics = parse_diagnostics("while (true) break;\nbreak;\n{ continue; }");
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(2, "can't use 'break' outside of a loop or switch".to_string()),
+                Diagnostic::new(3, "can't use 'continue' outside of a loop".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_undefined_label() {
+        let diagnostics = parse_diagnostics(
+            "inner: while (true) {}\nwhile (true) break inner;\nswitch (x) { case 1: break nope; }",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(2, "no enclosing loop labeled 'inner'".to_string()),
+                Diagnostic::new(3, "no enclosing loop labeled 'nope'".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_switch_statement() {
+        let statements = parse_source(
+            "switch (value) {\n  case 1:\n  case -1: print 1;\n  case [a, _] when a > 0: break;\n  case Point(x: 0, y: y): print y;\n  default: print 0;\n}",
+        );
+
+        assert_eq!(
+            statements,
+            vec![Statement::Switch(
+                Expression::Variable("value".to_string()),
+                vec![
+                    SwitchCase {
+                        pattern: Pattern::Constant(Expression::Integer(1)),
+                        guard: None,
+                        body: vec![],
+                    },
+                    SwitchCase {
+                        pattern: Pattern::Constant(Expression::Unary(
+                            UnaryOperator::Minus,
+                            Box::new(Expression::Integer(1))
+                        )),
+                        guard: None,
+                        body: vec![Statement::Print(Expression::Integer(1), 3)],
+                    },
+                    SwitchCase {
+                        pattern: Pattern::List(vec![
+                            Pattern::Binding("a".to_string()),
+                            Pattern::Wildcard
+                        ]),
+                        guard: Some(Expression::Binary(
+                            Box::new(Expression::Variable("a".to_string())),
+                            BinaryOperator::Greater,
+                            Box::new(Expression::Integer(0)),
+                        )),
+                        body: vec![Statement::Break(None)],
+                    },
+                    SwitchCase {
+                        pattern: Pattern::Object(
+                            "Point".to_string(),
+                            vec![
+                                ("x".to_string(), Pattern::Constant(Expression::Integer(0))),
+                                ("y".to_string(), Pattern::Binding("y".to_string())),
+                            ]
+                        ),
+                        guard: None,
+                        body: vec![Statement::Print(Expression::Variable("y".to_string()), 5)],
+                    },
+                ],
+                Some(vec![Statement::Print(Expression::Integer(0), 6)]),
+                1,
+            )]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_switch_expression() {
+        let statements = parse_source("var name = switch (n) { 0 => \"zero\", {\"n\": m} => m, _ => \"many\", };");
+
+        assert_eq!(
+            statements,
+            vec![Statement::Var(
+                Mutability::Var,
+                "name".to_string(),
+                None,
+                Some(Expression::Switch(
+                    Box::new(Expression::Variable("n".to_string())),
+                    vec![
+                        SwitchArm {
+                            pattern: Pattern::Constant(Expression::Integer(0)),
+                            guard: None,
+                            body: Expression::String("zero".to_string()),
+                        },
+                        SwitchArm {
+                            pattern: Pattern::Map(vec![(
+                                Expression::String("n".to_string()),
+                                Pattern::Binding("m".to_string())
+                            )]),
+                            guard: None,
+                            body: Expression::Variable("m".to_string()),
+                        },
+                        SwitchArm {
+                            pattern: Pattern::Wildcard,
+                            guard: None,
+                            body: Expression::String("many".to_string()),
+                        },
+                    ]
+                )),
+                1,
+            )]
+        );
+    }
+
+    #[test]
+    pub fn test_duplicate_default() {
+        let diagnostics = parse_diagnostics("switch (x) {\ndefault: print 1;\ndefault: print 2;\n}");
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(3, "a switch can only have one default case".to_string())]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_destructuring_declaration() {
+        let statements = parse_source("var [a, b] = pair;\nvar {name, \"age\": years} = person;\nvar {name: [first, _]} = person;");
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Destructure(
+                    Mutability::Var,
+                    Pattern::List(vec![
+                        Pattern::Binding("a".to_string()),
+                        Pattern::Binding("b".to_string())
+                    ]),
+                    Expression::Variable("pair".to_string()),
+                    1,
+                ),
+                Statement::Destructure(
+                    Mutability::Var,
+                    Pattern::Map(vec![
+                        (
+                            Expression::String("name".to_string()),
+                            Pattern::Binding("name".to_string())
+                        ),
+                        (
+                            Expression::String("age".to_string()),
+                            Pattern::Binding("years".to_string())
+                        ),
+                    ]),
+                    Expression::Variable("person".to_string()),
+                    2,
+                ),
+                Statement::Destructure(
+                    Mutability::Var,
+                    Pattern::Map(vec![(
+                        Expression::String("name".to_string()),
+                        Pattern::List(vec![Pattern::Binding("first".to_string()), Pattern::Wildcard]),
+                    )]),
+                    Expression::Variable("person".to_string()),
+                    3,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_destructuring() {
+        let diagnostics = parse_diagnostics(
+            "var [x, x] = v;\nvar {a, b: [a]} = m;\n{a, b} = m;\n[y, _, y] = v;\nswitch (v) { case [z, z]: break; }",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "'x' is bound more than once in the pattern".to_string()),
+                Diagnostic::new(2, "'a' is bound more than once in the pattern".to_string()),
+                Diagnostic::new(
+                    3,
+                    "can't assign to a map pattern, destructure the map in a declaration: var {...} = value;".to_string()
+                ),
+                Diagnostic::new(4, "'y' is bound more than once in the pattern".to_string()),
+                Diagnostic::new(5, "'z' is bound more than once in the pattern".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_uninitialized_final() {
+        let diagnostics = parse_diagnostics("final name;\nconst [a, b] = pair;");
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "final variable 'name' must be initialized".to_string()),
+                Diagnostic::new(2, "a destructuring declaration can't be const".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_swap() {
+        let statements = parse_source("[a, b] = [b, a];");
+
+        assert_eq!(
+            statements,
+            vec![Statement::Expression(Expression::Destructure(
+                Box::new(Pattern::List(vec![
+                    Pattern::Binding("a".to_string()),
+                    Pattern::Binding("b".to_string())
+                ])),
+                Box::new(Expression::List(vec![
+                    Expression::Variable("b".to_string()),
+                    Expression::Variable("a".to_string()),
+                ])),
+            ), 1)]
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_assignment_target() {
+        let diagnostics = parse_diagnostics("a + b = 1;\n[a, 1] = pair;");
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "invalid assignment target".to_string()),
+                Diagnostic::new(2, "invalid destructuring assignment target".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_compound_assignment() {
+        let statements = parse_source("a.b[i] += 1;\nnames ??= [];\n++counter;\nlist[0]--;");
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Expression(Expression::CompoundAssign(
+                    Box::new(Expression::Index(
+                        Box::new(Expression::Get(
+                            Box::new(Expression::Variable("a".to_string())),
+                            "b".to_string()
+                        )),
+                        Box::new(Expression::Variable("i".to_string())),
+                    )),
+                    AssignOperator::Plus,
+                    Box::new(Expression::Integer(1)),
+                ), 1),
+                Statement::Expression(Expression::CompoundAssign(
+                    Box::new(Expression::Variable("names".to_string())),
+                    AssignOperator::QuestionQuestion,
+                    Box::new(Expression::List(vec![])),
+                ), 2),
+                Statement::Expression(Expression::CompoundAssign(
+                    Box::new(Expression::Variable("counter".to_string())),
+                    AssignOperator::Plus,
+                    Box::new(Expression::Integer(1)),
+                ), 3),
+                Statement::Expression(Expression::Postfix(
+                    Box::new(Expression::Index(
+                        Box::new(Expression::Variable("list".to_string())),
+                        Box::new(Expression::Integer(0)),
+                    )),
+                    PostfixOperator::MinusMinus,
+                ), 4),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_call_and_set() {
+        let statements = parse_source("f(1, 2).x = -y.z;");
+
+        assert_eq!(
+            statements,
+            vec![Statement::Expression(Expression::Set(
+                Box::new(Expression::Call(
+                    Box::new(Expression::Variable("f".to_string())),
+                    vec![Expression::Integer(1), Expression::Integer(2)],
+                    vec![],
+                )),
+                "x".to_string(),
+                Box::new(Expression::Unary(
+                    UnaryOperator::Minus,
+                    Box::new(Expression::Get(
+                        Box::new(Expression::Variable("y".to_string())),
+                        "z".to_string()
+                    )),
+                )),
+            ), 1)]
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_update_target() {
+        let diagnostics = parse_diagnostics("f() += 1;\n1++;\n--(a);");
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "invalid target for '+='".to_string()),
+                Diagnostic::new(2, "invalid target for '++'".to_string()),
+                Diagnostic::new(3, "invalid target for '--'".to_string()),
+            ]
+        );
+    }
+
+    fn binary(left: Expression, operator: BinaryOperator, right: Expression) -> Expression {
+        Expression::Binary(Box::new(left), operator, Box::new(right))
+    }
+
+    #[test]
+    pub fn test_parse_bitwise_precedence() {
+        let statements = parse_source("a | b ^ c & d << 1 + e % 2;");
+
+        let shift = binary(
+            Expression::Variable("d".to_string()),
+            BinaryOperator::LessLess,
+            binary(
+                Expression::Integer(1),
+                BinaryOperator::Plus,
+                binary(
+                    Expression::Variable("e".to_string()),
+                    BinaryOperator::Percent,
+                    Expression::Integer(2),
+                ),
+            ),
+        );
+
+        assert_eq!(
+            statements,
+            vec![Statement::Expression(binary(
+                Expression::Variable("a".to_string()),
+                BinaryOperator::Pipe,
+                binary(
+                    Expression::Variable("b".to_string()),
+                    BinaryOperator::Caret,
+                    binary(
+                        Expression::Variable("c".to_string()),
+                        BinaryOperator::Ampersand,
+                        shift,
+                    ),
+                ),
+            ), 1)]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_exponent() {
+        let statements = parse_source("-2 ** 3 ** ~x;");
+
+        assert_eq!(
+            statements,
+            vec![Statement::Expression(Expression::Unary(
+                UnaryOperator::Minus,
+                Box::new(binary(
+                    Expression::Integer(2),
+                    BinaryOperator::StarStar,
+                    binary(
+                        Expression::Integer(3),
+                        BinaryOperator::StarStar,
+                        Expression::Unary(
+                            UnaryOperator::Tilde,
+                            Box::new(Expression::Variable("x".to_string()))
+                        ),
+                    ),
+                )),
+            ), 1)]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_if_null() {
+        let statements = parse_source("a or b ?? c ?? nil;");
+
+        assert_eq!(
+            statements,
+            vec![Statement::Expression(Expression::Logical(
+                Box::new(Expression::Logical(
+                    Box::new(Expression::Logical(
+                        Box::new(Expression::Variable("a".to_string())),
+                        LogicalOperator::Or,
+                        Box::new(Expression::Variable("b".to_string())),
+                    )),
+                    LogicalOperator::QuestionQuestion,
+                    Box::new(Expression::Variable("c".to_string())),
+                )),
+                LogicalOperator::QuestionQuestion,
+                Box::new(Expression::Nil),
+            ), 1)]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_null_aware_chain() {
+        let statements = parse_source("user?.address?.city;\nlist?[0].name(1);");
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Expression(Expression::NullAwareChain(Box::new(
+                    Expression::NullAwareGet(
+                        Box::new(Expression::NullAwareGet(
+                            Box::new(Expression::Variable("user".to_string())),
+                            "address".to_string(),
+                        )),
+                        "city".to_string(),
+                    )
+                )), 1),
+                Statement::Expression(Expression::NullAwareChain(Box::new(Expression::Call(
+                    Box::new(Expression::Get(
+                        Box::new(Expression::NullAwareIndex(
+                            Box::new(Expression::Variable("list".to_string())),
+                            Box::new(Expression::Integer(0)),
+                        )),
+                        "name".to_string(),
+                    )),
+                    vec![Expression::Integer(1)],
+                    vec![],
+                ))), 2),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_ternary() {
+        let statements = parse_source("x = a ?? b ? [1] : c ? 2 : 3;");
+
+        assert_eq!(
+            statements,
+            vec![Statement::Expression(Expression::Assign(
+                "x".to_string(),
+                Box::new(Expression::Ternary(
+                    Box::new(Expression::Logical(
+                        Box::new(Expression::Variable("a".to_string())),
+                        LogicalOperator::QuestionQuestion,
+                        Box::new(Expression::Variable("b".to_string())),
+                    )),
+                    Box::new(Expression::List(vec![Expression::Integer(1)])),
+                    Box::new(Expression::Ternary(
+                        Box::new(Expression::Variable("c".to_string())),
+                        Box::new(Expression::Integer(2)),
+                        Box::new(Expression::Integer(3)),
+                    )),
+                )),
+            ), 1)]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_conditional_index() {
+        let statements = parse_source("a ?[0] : 1;\nc ? a?[0] : b ?[1, 2] : 3;");
+
+        let index = Expression::NullAwareChain(Box::new(Expression::NullAwareIndex(
+            Box::new(Expression::Variable("a".to_string())),
+            Box::new(Expression::Integer(0)),
+        )));
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Expression(Expression::Ternary(
+                    Box::new(Expression::Variable("a".to_string())),
+                    Box::new(Expression::List(vec![Expression::Integer(0)])),
+                    Box::new(Expression::Integer(1)),
+                ), 1),
+                Statement::Expression(Expression::Ternary(
+                    Box::new(Expression::Variable("c".to_string())),
+                    Box::new(index),
+                    Box::new(Expression::Ternary(
+                        Box::new(Expression::Variable("b".to_string())),
+                        Box::new(Expression::List(vec![Expression::Integer(1), Expression::Integer(2)])),
+                        Box::new(Expression::Integer(3)),
+                    )),
+                ), 2),
+            ]
+        );
+
+        let diagnostics = parse_diagnostics("switch (x) { case y when a?[0]: break; }\nprint a ?[0;");
+        assert_eq!(diagnostics, vec![Diagnostic::new(2, "expect ']' after index".to_string())]);
+    }
+
+    #[test]
+    pub fn test_parse_cascade() {
+        let statements = parse_source("builder..add(1)..name = a ?? \"x\"..build();");
+
+        let receiver = || Box::new(Expression::CascadeReceiver);
+
+        assert_eq!(
+            statements,
+            vec![Statement::Expression(Expression::Cascade(
+                Box::new(Expression::Variable("builder".to_string())),
+                vec![
+                    Expression::Call(
+                        Box::new(Expression::Get(receiver(), "add".to_string())),
+                        vec![Expression::Integer(1)],
+                        vec![],
+                    ),
+                    Expression::Set(
+                        receiver(),
+                        "name".to_string(),
+                        Box::new(Expression::Logical(
+                            Box::new(Expression::Variable("a".to_string())),
+                            LogicalOperator::QuestionQuestion,
+                            Box::new(Expression::String("x".to_string())),
+                        )),
+                    ),
+                    Expression::Call(
+                        Box::new(Expression::Get(receiver(), "build".to_string())),
+                        vec![],
+                        vec![],
+                    ),
+                ],
+            ), 1)]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_cascade_or_range() {
+        let statements = parse_source(
+            "0..xs.length;\na + b..address.city = c;\nfor (var i in 0..len(xs)) {}\nfor (var i in lo..(max(a, b))) {}\n(list)..add(1);",
+        );
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Expression(Expression::Range(
+                    Box::new(Expression::Integer(0)),
+                    RangeOperator::DotDot,
+                    Box::new(Expression::Get(
+                        Box::new(Expression::Variable("xs".to_string())),
+                        "length".to_string(),
+                    )),
+                ), 1),
+                Statement::Expression(Expression::Cascade(
+                    Box::new(binary(
+                        Expression::Variable("a".to_string()),
+                        BinaryOperator::Plus,
+                        Expression::Variable("b".to_string()),
+                    )),
+                    vec![Expression::Set(
+                        Box::new(Expression::Get(
+                            Box::new(Expression::CascadeReceiver),
+                            "address".to_string(),
+                        )),
+                        "city".to_string(),
+                        Box::new(Expression::Variable("c".to_string())),
+                    )],
+                ), 2),
+                Statement::ForIn(
+                    "i".to_string(),
+                    Expression::Range(
+                        Box::new(Expression::Integer(0)),
+                        RangeOperator::DotDot,
+                        Box::new(Expression::Call(
+                            Box::new(Expression::Variable("len".to_string())),
+                            vec![Expression::Variable("xs".to_string())],
+                            vec![],
+                        )),
+                    ),
+                    Box::new(Statement::Block(vec![])),
+                    3,
+                ),
+                Statement::ForIn(
+                    "i".to_string(),
+                    Expression::Range(
+                        Box::new(Expression::Variable("lo".to_string())),
+                        RangeOperator::DotDot,
+                        Box::new(Expression::Grouping(Box::new(Expression::Call(
+                            Box::new(Expression::Variable("max".to_string())),
+                            vec![Expression::Variable("a".to_string()), Expression::Variable("b".to_string())],
+                            vec![],
+                        )))),
+                    ),
+                    Box::new(Statement::Block(vec![])),
+                    4,
+                ),
+                Statement::Expression(Expression::Cascade(
+                    Box::new(Expression::Grouping(Box::new(Expression::Variable("list".to_string())))),
+                    vec![Expression::Call(
+                        Box::new(Expression::Get(Box::new(Expression::CascadeReceiver), "add".to_string())),
+                        vec![Expression::Integer(1)],
+                        vec![],
+                    )],
+                ), 5),
+            ]
+        );
+
+        let diagnostics = parse_diagnostics("for (var i in lo..max(a, b)) {}\nvar xs = list..add(1);");
+        let ambiguous = "ambiguous '..', write 'start..(end)' for a range or '(receiver)..method()' for a cascade";
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(1, ambiguous.to_string()), Diagnostic::new(2, ambiguous.to_string())]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_type_check() {
+        let statements = parse_source("x is String and y is! List;\n(z as Point).x;");
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Expression(Expression::Logical(
+                    Box::new(Expression::TypeCheck(
+                        Box::new(Expression::Variable("x".to_string())),
+                        TypeOperator::Is,
+                        Type::String,
+                    )),
+                    LogicalOperator::And,
+                    Box::new(Expression::TypeCheck(
+                        Box::new(Expression::Variable("y".to_string())),
+                        TypeOperator::IsNot,
+                        Type::List,
+                    )),
+                ), 1),
+                Statement::Expression(Expression::Get(
+                    Box::new(Expression::Grouping(Box::new(Expression::TypeCheck(
+                        Box::new(Expression::Variable("z".to_string())),
+                        TypeOperator::As,
+                        Type::Class("Point".to_string()),
+                    )))),
+                    "x".to_string(),
+                ), 2),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_generic_class() {
+        let statements = parse_source(
+            "class Box<T> extends Base<T> {\n  var items: List<List<T>>= [];\n  first(): T? {\n    return nil;\n  }\n}",
+        );
+
+        let t = || Type::Parameter("T".to_string());
+        let generic = |base: Type, arguments: Vec<Type>| Type::Generic(Box::new(base), arguments);
+
+        assert_eq!(
+            statements,
+            vec![Statement::Class(Class {
+                name: "Box".to_string(),
+                type_parameters: vec!["T".to_string()],
+                superclass: Some(generic(Type::Class("Base".to_string()), vec![t()])),
+                fields: vec![Field {
+                    name: "items".to_string(),
+                    annotation: Some(generic(Type::List, vec![generic(Type::List, vec![t()])])),
+                    initializer: Some(Expression::List(vec![])),
+                    line: 2,
+                }],
+                methods: vec![Function {
+                    name: "first".to_string(),
+                    type_parameters: vec![],
+                    parameters: vec![],
+                    return_type: Some(Type::Nullable(Box::new(t()))),
+                    generator: false,
+                    asynchronous: false,
+                    body: vec![Statement::Return(Some(Expression::Nil), 4)],
+                    line: 3,
+                }],
+                line: 1,
+            })]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_generic_function() {
+        let statements = parse_source("func first<T>(xs: List<T>): T {}\nvar t: T;\nx < y > z;");
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Function(Function {
+                    name: "first".to_string(),
+                    type_parameters: vec!["T".to_string()],
+                    parameters: vec![Parameter {
+                        name: "xs".to_string(),
+                        annotation: Some(Type::Generic(
+                            Box::new(Type::List),
+                            vec![Type::Parameter("T".to_string())]
+                        )),
+                        kind: ParameterKind::Positional,
+                        default: None,
+                    }],
+                    return_type: Some(Type::Parameter("T".to_string())),
+                    generator: false,
+                    asynchronous: false,
+                    body: vec![],
+                    line: 1,
+                }),
+                // the type parameter is out of scope after the function
+                Statement::Var(Mutability::Var, "t".to_string(), Some(Type::Class("T".to_string())), None, 2),
+                Statement::Expression(binary(
+                    binary(
+                        Expression::Variable("x".to_string()),
+                        BinaryOperator::Less,
+                        Expression::Variable("y".to_string()),
+                    ),
+                    BinaryOperator::Greater,
+                    Expression::Variable("z".to_string()),
+                ), 3),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_parameters() {
+        let statements = parse_source(
+            "func greet(name, {greeting = \"Hi\", required times: int}) {}\nfunc pad(text, [width = 8]) {}\ngreet(\"Ada\", times: 2);",
+        );
+
+        let parameter = |name: &str, annotation: Option<Type>, kind: ParameterKind, default: Option<Expression>| Parameter {
+            name: name.to_string(),
+            annotation,
+            kind,
+            default,
+        };
+        let function = |name: &str, parameters: Vec<Parameter>, line: i32| {
+            Statement::Function(Function {
+                name: name.to_string(),
+                type_parameters: vec![],
+                parameters,
+                return_type: None,
+                generator: false,
+                asynchronous: false,
+                body: vec![],
+                line,
+            })
+        };
+
+        assert_eq!(
+            statements,
+            vec![
+                function(
+                    "greet",
+                    vec![
+                        parameter("name", None, ParameterKind::Positional, None),
+                        parameter("greeting", None, ParameterKind::Named, Some(Expression::String("Hi".to_string()))),
+                        parameter("times", Some(Type::Int), ParameterKind::Required, None),
+                    ],
+                    1,
+                ),
+                function(
+                    "pad",
+                    vec![
+                        parameter("text", None, ParameterKind::Positional, None),
+                        parameter("width", None, ParameterKind::Optional, Some(Expression::Integer(8))),
+                    ],
+                    2,
+                ),
+                Statement::Expression(
+                    Expression::Call(
+                        Box::new(Expression::Variable("greet".to_string())),
+                        vec![Expression::String("Ada".to_string())],
+                        vec![("times".to_string(), Expression::Integer(2))],
+                    ),
+                    3,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_parameters() {
+        let diagnostics = parse_diagnostics("func f(a = 1, {required b = 2}) {}\nf(b: 1, b: 2);");
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "required parameter 'a' can't have a default value".to_string()),
+                Diagnostic::new(1, "required parameter 'b' can't have a default value".to_string()),
+                Diagnostic::new(2, "argument 'b' is already passed".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_rest_parameter() {
+        let statements = parse_source("func log(level, ...messages: String) {}\nlog(1, ...lines, \"done\");");
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Function(Function {
+                    name: "log".to_string(),
+                    type_parameters: vec![],
+                    parameters: vec![
+                        Parameter {
+                            name: "level".to_string(),
+                            annotation: None,
+                            kind: ParameterKind::Positional,
+                            default: None,
+                        },
+                        Parameter {
+                            name: "messages".to_string(),
+                            annotation: Some(Type::String),
+                            kind: ParameterKind::Rest,
+                            default: None,
+                        },
+                    ],
+                    return_type: None,
+                    generator: false,
+                    asynchronous: false,
+                    body: vec![],
+                    line: 1,
+                }),
+                Statement::Expression(
+                    Expression::Call(
+                        Box::new(Expression::Variable("log".to_string())),
+                        vec![
+                            Expression::Integer(1),
+                            Expression::Spread(Box::new(Expression::Variable("lines".to_string()))),
+                            Expression::String("done".to_string()),
+                        ],
+                        vec![],
+                    ),
+                    2,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_generator() {
+        let statements = parse_source("func* count(n) {\n  yield n;\n  return;\n}");
+
+        assert_eq!(
+            statements,
+            vec![Statement::Function(Function {
+                name: "count".to_string(),
+                type_parameters: vec![],
+                parameters: vec![Parameter {
+                    name: "n".to_string(),
+                    annotation: None,
+                    kind: ParameterKind::Positional,
+                    default: None,
+                }],
+                return_type: None,
+                generator: true,
+                asynchronous: false,
+                body: vec![
+                    Statement::Yield(Expression::Variable("n".to_string()), 2),
+                    Statement::Return(None, 3),
+                ],
+                line: 1,
+            })]
+        );
+    }
+
+    #[test]
+    pub fn test_yield_outside_generator() {
+        let diagnostics = parse_diagnostics(
+            "yield 1;\nfunc f() { yield 2; }\nfunc* g() {\n  func h() { yield 3; }\n  return 4;\n}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "can't use 'yield' outside of a generator".to_string()),
+                Diagnostic::new(2, "can't use 'yield' outside of a generator".to_string()),
+                Diagnostic::new(4, "can't use 'yield' outside of a generator".to_string()),
+                Diagnostic::new(5, "can't return a value from a generator".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_async_function() {
+        let statements = parse_source("async func load(url) {\n  return await fetch(url);\n}");
+
+        assert_eq!(
+            statements,
+            vec![Statement::Function(Function {
+                name: "load".to_string(),
+                type_parameters: vec![],
+                parameters: vec![Parameter {
+                    name: "url".to_string(),
+                    annotation: None,
+                    kind: ParameterKind::Positional,
+                    default: None,
+                }],
+                return_type: None,
+                generator: false,
+                asynchronous: true,
+                body: vec![Statement::Return(
+                    Some(Expression::Await(Box::new(Expression::Call(
+                        Box::new(Expression::Variable("fetch".to_string())),
+                        vec![Expression::Variable("url".to_string())],
+                        vec![],
+                    )))),
+                    2,
+                )],
+                line: 1,
+            })]
+        );
+    }
+
+    #[test]
+    pub fn test_await_outside_async_function() {
+        let diagnostics = parse_diagnostics(
+            "await ready;\nasync func f() {\n  func g() { await ready; }\n}\nasync func* h() {}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "can't use 'await' outside of an async function".to_string()),
+                Diagnostic::new(3, "can't use 'await' outside of an async function".to_string()),
+                Diagnostic::new(5, "a generator can't be async".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_keyword_property_name() {
+        let statements = parse_source("Fiber.yield(1);\nnext?.class;");
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Expression(
+                    Expression::Call(
+                        Box::new(Expression::Get(
+                            Box::new(Expression::Variable("Fiber".to_string())),
+                            "yield".to_string(),
+                        )),
+                        vec![Expression::Integer(1)],
+                        vec![],
+                    ),
+                    1,
+                ),
+                Statement::Expression(
+                    Expression::NullAwareChain(Box::new(Expression::NullAwareGet(
+                        Box::new(Expression::Variable("next".to_string())),
+                        "class".to_string(),
+                    ))),
+                    2,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_operators() {
+        let statements = parse_source(
+            "class Vector {\n  operator +(other) {}\n  operator -() {}\n  operator [](i) {}\n  operator []=(i, value) {}\n  operator(x) {}\n}",
+        );
+
+        let names: Vec<String> = match &statements[0] {
+            Statement::Class(class) => class.methods.iter().map(|method| method.name.clone()).collect(),
+            statement => panic!("expected a class, got {:?}", statement),
+        };
+        assert_eq!(names, vec!["+", "unary-", "[]", "[]=", "operator"]);
+    }
+
+    #[test]
+    pub fn test_operator_arity() {
+        let diagnostics = parse_diagnostics(
+            "class Vector {\n  operator +(a, b) {}\n  operator ~(a) {}\n  operator []=(i) {}\n  operator ==([other]) {}\n}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(2, "operator '+' must take 1 parameter".to_string()),
+                Diagnostic::new(3, "operator '~' must take no parameters".to_string()),
+                Diagnostic::new(4, "operator '[]=' must take 2 parameters".to_string()),
+                Diagnostic::new(5, "operator '==' must take 1 parameter".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_extension() {
+        let statements = parse_source("extension Shout on String {\n  shout(): String { return this; }\n}\nvar extension = 1;");
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Extension(Extension {
+                    name: "Shout".to_string(),
+                    on: Type::String,
+                    methods: vec![Function {
+                        name: "shout".to_string(),
+                        type_parameters: vec![],
+                        parameters: vec![],
+                        return_type: Some(Type::String),
+                        generator: false,
+                        asynchronous: false,
+                        body: vec![Statement::Return(Some(Expression::This), 2)],
+                        line: 2,
+                    }],
+                    line: 1,
+                }),
+                Statement::Var(
+                    Mutability::Var,
+                    "extension".to_string(),
+                    None,
+                    Some(Expression::Integer(1)),
+                    4,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_extension_field() {
+        let diagnostics = parse_diagnostics("extension Counted on List {\n  var count = 0;\n}");
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(2, "extension 'Counted' can't declare field 'count'".to_string())]
+        );
+    }
+
+    #[test]
+    pub fn test_import_export() {
+        let statements = parse_source("import \"lib/math.ql\" as math;\nimport \"util.ql\";\nexport final pi = 3;");
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Import(Import {
+                    path: "lib/math.ql".to_string(),
+                    alias: Some("math".to_string()),
+                    exports: None,
+                    line: 1,
+                }),
+                Statement::Import(Import {
+                    path: "util.ql".to_string(),
+                    alias: None,
+                    exports: None,
+                    line: 2,
+                }),
+                Statement::Export(Box::new(Statement::Var(
+                    Mutability::Final,
+                    "pi".to_string(),
+                    None,
+                    Some(Expression::Integer(3)),
+                    3
+                ))),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_nested_import_export() {
+        let diagnostics = parse_diagnostics("func f() {\n  import \"a.ql\";\n}\nexport print 1;\n{\n  export var x;\n}");
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(2, "'import' is only allowed at the top level".to_string()),
+                Diagnostic::new(4, "only declarations can be exported".to_string()),
+                Diagnostic::new(6, "'export' is only allowed at the top level".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_syntax_errors() {
+        let diagnostics = parse_diagnostics(
+            "var a = ;\nprint 1\nvar b = {\"a\": 1};\nfunc f(x, [y], z) {}\nextension E List {}\nimport 1;\nclass A { 1; }\nprint (2;\na ! b;\na!;\nvar $x = 1;",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "expect expression".to_string()),
+                Diagnostic::new(2, "expect ';' after value".to_string()),
+                Diagnostic::new(3, "expect expression".to_string()),
+                Diagnostic::new(4, "optional and named parameters must come last".to_string()),
+                Diagnostic::new(5, "expect 'on' after extension name".to_string()),
+                Diagnostic::new(6, "expect module path after 'import'".to_string()),
+                Diagnostic::new(7, "expect field or method declaration".to_string()),
+                Diagnostic::new(8, "expect ')' after expression".to_string()),
+                Diagnostic::new(9, "expect ';' after expression".to_string()),
+                Diagnostic::new(10, "expect ';' after expression".to_string()),
+                Diagnostic::new(11, "expect variable name".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_this_outside_class() {
+        let diagnostics = parse_diagnostics("print this;");
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(1, "can't use 'this' outside of a class".to_string())]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_super() {
+        let statements = parse_source("class A extends B { init() { super.init(1); } }");
+
+        match &statements[0] {
+            Statement::Class(class) => assert_eq!(
+                class.methods[0].body,
+                vec![Statement::Expression(Expression::Call(
+                    Box::new(Expression::Get(Box::new(Expression::Super), "init".to_string())),
+                    vec![Expression::Integer(1)],
+                    vec![],
+                ), 1)]
+            ),
+            statement => panic!("expected a class, got {:?}", statement),
+        }
+
+        let diagnostics = parse_diagnostics("print super.f();\nclass A extends B { f() { return super; } }");
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "can't use 'super' outside of a class".to_string()),
+                Diagnostic::new(2, "expect '.' after 'super'".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_enum() {
+        let statements = parse_source(
+            "enum Planet {\n  mercury(3.7),\n  earth(9.8);\n  var gravity: double;\n}\nswitch (planet) { case Planet.earth: break; }",
+        );
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Enum(Enum {
+                    name: "Planet".to_string(),
+                    values: vec![
+                        EnumValue {
+                            name: "mercury".to_string(),
+                            arguments: vec![Expression::Number(3.7)],
+                            named: vec![],
+                            line: 2,
+                        },
+                        EnumValue {
+                            name: "earth".to_string(),
+                            arguments: vec![Expression::Number(9.8)],
+                            named: vec![],
+                            line: 3,
+                        },
+                    ],
+                    fields: vec![Field {
+                        name: "gravity".to_string(),
+                        annotation: Some(Type::Double),
+                        initializer: None,
+                        line: 4,
+                    }],
+                    methods: vec![],
+                    line: 1,
+                }),
+                Statement::Switch(
+                    Expression::Variable("planet".to_string()),
+                    vec![SwitchCase {
+                        pattern: Pattern::Constant(Expression::Get(
+                            Box::new(Expression::Variable("Planet".to_string())),
+                            "earth".to_string(),
+                        )),
+                        guard: None,
+                        body: vec![Statement::Break(None)],
+                    }],
+                    None,
+                    6,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_duplicate_enum_value() {
+        let diagnostics = parse_diagnostics("enum Color { red, green, red }\nenum Empty {}");
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(1, "enum value 'red' is already declared".to_string()),
+                Diagnostic::new(2, "enum 'Empty' must declare at least one value".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_record() {
+        let statements = parse_source("(1);\n(1,);\n(1, y: \"a\");\n();\nvar point: (x: int, y: int)? = nil;");
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Expression(Expression::Grouping(Box::new(Expression::Integer(1))), 1),
+                Statement::Expression(Expression::Record(vec![Expression::Integer(1)], vec![]), 2),
+                Statement::Expression(Expression::Record(
+                    vec![Expression::Integer(1)],
+                    vec![("y".to_string(), Expression::String("a".to_string()))]
+                ), 3),
+                Statement::Expression(Expression::Record(vec![], vec![]), 4),
+                Statement::Var(
+                    Mutability::Var,
+                    "point".to_string(),
+                    Some(Type::Nullable(Box::new(Type::Record(
+                        vec![],
+                        vec![("x".to_string(), Type::Int), ("y".to_string(), Type::Int)]
+                    )))),
+                    Some(Expression::Nil),
+                    5,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_try() {
+        let statements = parse_source(
+            "try {\n  throw \"oops\";\n} catch (e, stack) {\n  print e;\n} finally {\n  close();\n}\nvar x = y ?? throw \"missing\";",
+        );
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Try(Try {
+                    body: vec![Statement::Expression(
+                        Expression::Throw(Box::new(Expression::String("oops".to_string()))),
+                        2
+                    )],
+                    catch: Some(Catch {
+                        exception: "e".to_string(),
+                        stack: Some("stack".to_string()),
+                        body: vec![Statement::Print(Expression::Variable("e".to_string()), 4)],
+                    }),
+                    finally: Some(vec![Statement::Expression(
+                        Expression::Call(Box::new(Expression::Variable("close".to_string())), vec![], vec![]),
+                        6
+                    )]),
+                }),
+                Statement::Var(
+                    Mutability::Var,
+                    "x".to_string(),
+                    None,
+                    Some(Expression::Logical(
+                        Box::new(Expression::Variable("y".to_string())),
+                        LogicalOperator::QuestionQuestion,
+                        Box::new(Expression::Throw(Box::new(Expression::String("missing".to_string())))),
+                    )),
+                    8,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_try_without_handler() {
+        let diagnostics = parse_diagnostics("try { risky(); }");
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(1, "a try needs a catch or a finally clause".to_string())]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_assert() {
+        let source = "assert(x > 0, \"x must be positive\");\nassert(ready);";
+        let statements = parse_source(source);
+
+        assert_eq!(
+            statements,
+            vec![
+                Statement::Assert(
+                    binary(Expression::Variable("x".to_string()), BinaryOperator::Greater, Expression::Integer(0)),
+                    Some(Expression::String("x must be positive".to_string())),
+                    1,
+                ),
+                Statement::Assert(Expression::Variable("ready".to_string()), None, 2),
+            ]
+        );
+
+        let mut scanner = Scanner::new(source.to_string());
+        let mut parser = Parser::new(scanner.scan().clone());
+        parser.asserts = false;
+
+        assert_eq!(parse_program(&mut parser), vec![Statement::Block(vec![]), Statement::Block(vec![])]);
+    }
+
+    #[test]
+    pub fn test_expression_source() {
+        let sources = [
+            "a.b(1, [2, 3]) ?? -c?[0].d is int",
+            "(x + 1) * y ** 2 >= 0 and !done",
+            "list..add(1)..length = 0",
+            "r = switch (p) { [a, _] when a > 0 => (a, name: \"n\"), {name} => 0..=10, _ => throw nil }",
+        ];
+
+        for source in sources {
+            let statements = parse_source(source);
+            match &statements[..] {
+                [Statement::Expression(expression, _)] => assert_eq!(expression.to_string(), source),
+                _ => panic!("expect a single expression"),
+            }
+        }
+
+        // operands nested without a grouping are parenthesized as the tree holds them
+        let x = || Expression::Variable("x".to_string());
+        let negate = |operand| Expression::Unary(UnaryOperator::Minus, Box::new(operand));
+        let expressions = [
+            (negate(negate(x())), "-(-x)"),
+            (negate(binary(Expression::Integer(2), BinaryOperator::StarStar, x())), "-(2 ** x)"),
+            (binary(x(), BinaryOperator::Minus, binary(x(), BinaryOperator::Minus, x())), "x - (x - x)"),
+            (binary(binary(x(), BinaryOperator::Minus, x()), BinaryOperator::Minus, x()), "x - x - x"),
+            (binary(binary(x(), BinaryOperator::Plus, x()), BinaryOperator::Star, negate(x())), "(x + x) * -x"),
+            (binary(negate(x()), BinaryOperator::StarStar, binary(x(), BinaryOperator::StarStar, x())), "(-x) ** x ** x"),
+        ];
+
+        for (expression, source) in expressions {
+            assert_eq!(expression.to_string(), source);
+        }
+
+        let statements = parse_source("assert(-(-x) == - -1);");
+        match &statements[..] {
+            [Statement::Assert(condition, _, _)] => assert_eq!(condition.to_string(), "-(-x) == -(-1)"),
+            _ => panic!("expect an assert"),
+        }
     }
 }
@@ -0,0 +1,63 @@
+use core::fmt;
+
+use crate::expression::Expression;
+
+/// Patterns are matched against a value by `switch` cases and arms.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Pattern {
+    /// `_`, matches anything without binding it
+    Wildcard,
+    /// A literal or a qualified constant such as `Color.red`, matches values equal to it
+    Constant(Expression),
+    /// A bare name, matches anything and binds the value to it
+    Binding(String),
+    /// `[first, second]`, matches lists of exactly that length
+    List(Vec<Pattern>),
+    /// `{"key": value}`, matches maps holding every key. A name key, as in
+    /// `{name: value}` or the shorthand `{name}`, reads the `"name"` key of a
+    /// map or the `name` field of an instance.
+    Map(Vec<(Expression, Pattern)>),
+    /// `Point(x: 0, y: y)`, matches instances of the class and then their fields
+    Object(String, Vec<(String, Pattern)>),
+}
+
+impl Pattern {
+    /// Names bound by the pattern, in order.
+    pub fn bindings(&self) -> Vec<&str> {
+        match self {
+            Pattern::Binding(name) => vec![name],
+            Pattern::List(elements) => elements.iter().flat_map(|element| element.bindings()).collect(),
+            Pattern::Map(entries) => entries.iter().flat_map(|(_, value)| value.bindings()).collect(),
+            Pattern::Object(_, fields) => fields.iter().flat_map(|(_, value)| value.bindings()).collect(),
+            Pattern::Wildcard | Pattern::Constant(_) => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Constant(constant) => write!(f, "{}", constant),
+            Pattern::Binding(name) => write!(f, "{}", name),
+            Pattern::List(elements) => {
+                let elements: Vec<String> = elements.iter().map(|element| element.to_string()).collect();
+                write!(f, "[{}]", elements.join(", "))
+            }
+            Pattern::Map(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| match (key, value) {
+                        (Expression::String(key), Pattern::Binding(name)) if key == name => name.clone(),
+                        _ => format!("{}: {}", key, value),
+                    })
+                    .collect();
+                write!(f, "{{{}}}", entries.join(", "))
+            }
+            Pattern::Object(class, fields) => {
+                let fields: Vec<String> = fields.iter().map(|(name, value)| format!("{}: {}", name, value)).collect();
+                write!(f, "{}({})", class, fields.join(", "))
+            }
+        }
+    }
+}
@@ -0,0 +1,754 @@
+use std::collections::HashMap;
+
+use crate::{
+    diagnostic::Diagnostic,
+    expression::{BinaryOperator, Expression, LogicalOperator, UnaryOperator},
+    pattern::Pattern,
+    statement::{Field, Function, Mutability, Statement},
+};
+
+/// A variable in scope.
+struct Declaration {
+    mutability: Mutability,
+    line: i32,
+    /// the value of a `const`, once computed
+    value: Option<Expression>,
+}
+
+/// Rejects assignments to `final` and `const` variables and to the elements of
+/// a `const` list, and computes the value of every `const` at compile time,
+/// replacing its initializer with it.
+pub struct Resolver {
+    scopes: Vec<HashMap<String, Declaration>>,
+    /// line of the statement being resolved
+    line: i32,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Resolver {
+    pub fn new() -> Resolver {
+        Resolver {
+            scopes: Vec::new(),
+            line: 1,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, message: String) {
+        self.diagnostics.push(Diagnostic::new(self.line, message));
+    }
+
+    fn declare(&mut self, name: &str, declaration: Declaration) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), declaration);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Declaration> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+}
+
+pub fn resolve_program(resolver: &mut Resolver, statements: &mut [Statement]) {
+    resolve_block(resolver, statements);
+}
+
+fn resolve_block(resolver: &mut Resolver, statements: &mut [Statement]) {
+    resolver.scopes.push(HashMap::new());
+    for statement in statements.iter_mut() {
+        resolve_statement(resolver, statement);
+    }
+    // a function may be called once the whole block has run, its body sees every declaration
+    for statement in statements.iter_mut() {
+        resolve_bodies(resolver, statement);
+    }
+    resolver.scopes.pop();
+}
+
+/// Resolves the bodies of the functions and methods a statement declares.
+fn resolve_bodies(resolver: &mut Resolver, statement: &mut Statement) {
+    let methods = match statement {
+        Statement::Function(function) => std::slice::from_mut(function),
+        Statement::Class(class) => &mut class.methods[..],
+        Statement::Extension(extension) => &mut extension.methods[..],
+        Statement::Enum(declaration) => &mut declaration.methods[..],
+        Statement::Export(declaration) => return resolve_bodies(resolver, declaration),
+        _ => return,
+    };
+
+    for method in methods {
+        resolve_body(resolver, method);
+    }
+}
+
+fn resolve_statement(resolver: &mut Resolver, statement: &mut Statement) {
+    match statement {
+        Statement::Expression(expression, line)
+        | Statement::Print(expression, line)
+        | Statement::Yield(expression, line) => {
+            resolver.line = *line;
+            resolve_expression(resolver, expression);
+        }
+        Statement::Var(mutability, name, _, initializer, line) => {
+            resolver.line = *line;
+
+            let mut value = None;
+            if let Some(initializer) = initializer {
+                resolve_expression(resolver, initializer);
+
+                if *mutability == Mutability::Const {
+                    match evaluate(resolver, initializer) {
+                        Some(constant) => {
+                            *initializer = constant.clone();
+                            value = Some(constant);
+                        }
+                        None => resolver.error(format!(
+                            "const variable '{}' must be initialized with a constant expression",
+                            name
+                        )),
+                    }
+                }
+            }
+
+            let declaration = Declaration {
+                mutability: *mutability,
+                line: *line,
+                value,
+            };
+            resolver.declare(name, declaration);
+        }
+        Statement::Destructure(mutability, pattern, value, line) => {
+            resolver.line = *line;
+            resolve_expression(resolver, value);
+            declare_pattern(resolver, pattern, *mutability);
+        }
+        Statement::Block(statements) => resolve_block(resolver, statements),
+        Statement::If(condition, then_branch, else_branch) => {
+            resolve_expression(resolver, condition);
+            resolve_statement(resolver, then_branch);
+            if let Some(else_branch) = else_branch {
+                resolve_statement(resolver, else_branch);
+            }
+        }
+        Statement::While(condition, body) => {
+            resolve_expression(resolver, condition);
+            resolve_statement(resolver, body);
+        }
+        Statement::For(initializer, condition, increment, body) => {
+            resolver.scopes.push(HashMap::new());
+            if let Some(initializer) = initializer {
+                resolve_statement(resolver, initializer);
+            }
+            if let Some(condition) = condition {
+                resolve_expression(resolver, condition);
+            }
+            if let Some(increment) = increment {
+                resolve_expression(resolver, increment);
+            }
+            resolve_statement(resolver, body);
+            resolver.scopes.pop();
+        }
+        Statement::ForIn(name, iterable, body, line) => {
+            resolver.line = *line;
+            resolve_expression(resolver, iterable);
+            resolver.scopes.push(HashMap::new());
+            declare_variable(resolver, name);
+            resolve_statement(resolver, body);
+            resolver.scopes.pop();
+        }
+        Statement::Labeled(_, statement) => resolve_statement(resolver, statement),
+        Statement::Break(_) | Statement::Continue(_) => {}
+        Statement::Switch(value, cases, default, line) => {
+            resolver.line = *line;
+            resolve_expression(resolver, value);
+
+            for case in cases {
+                resolver.scopes.push(HashMap::new());
+                declare_pattern(resolver, &case.pattern, Mutability::Var);
+                if let Some(guard) = &case.guard {
+                    resolve_expression(resolver, guard);
+                }
+                resolve_block(resolver, &mut case.body);
+                resolver.scopes.pop();
+            }
+
+            if let Some(default) = default {
+                resolve_block(resolver, default);
+            }
+        }
+        Statement::Function(function) => resolve_function(resolver, function),
+        Statement::Return(value, line) => {
+            resolver.line = *line;
+            if let Some(value) = value {
+                resolve_expression(resolver, value);
+            }
+        }
+        Statement::Assert(condition, message, line) => {
+            resolver.line = *line;
+            resolve_expression(resolver, condition);
+            if let Some(message) = message {
+                resolve_expression(resolver, message);
+            }
+        }
+        Statement::Class(class) => resolve_members(resolver, &class.fields, &mut class.methods),
+        Statement::Extension(extension) => resolve_members(resolver, &[], &mut extension.methods),
+        Statement::Enum(declaration) => {
+            for value in &declaration.values {
+                resolver.line = value.line;
+                for argument in value.arguments.iter().chain(value.named.iter().map(|(_, argument)| argument)) {
+                    resolve_expression(resolver, argument);
+                }
+            }
+            resolve_members(resolver, &declaration.fields, &mut declaration.methods);
+        }
+        Statement::Import(import) => {
+            resolver.line = import.line;
+
+            // the bindings of an import belong to the module it loads
+            let names = match (&import.alias, &import.exports) {
+                (Some(alias), _) => vec![alias.clone()],
+                (None, Some(exports)) => exports.clone(),
+                (None, None) => Vec::new(),
+            };
+            for name in names {
+                let declaration = Declaration {
+                    mutability: Mutability::Final,
+                    line: import.line,
+                    value: None,
+                };
+                resolver.declare(&name, declaration);
+            }
+        }
+        Statement::Export(declaration) => resolve_statement(resolver, declaration),
+        Statement::Try(statement) => {
+            resolve_block(resolver, &mut statement.body);
+
+            if let Some(catch) = &mut statement.catch {
+                resolver.scopes.push(HashMap::new());
+                declare_variable(resolver, &catch.exception);
+                if let Some(stack) = &catch.stack {
+                    declare_variable(resolver, stack);
+                }
+                resolve_block(resolver, &mut catch.body);
+                resolver.scopes.pop();
+            }
+
+            if let Some(finally) = &mut statement.finally {
+                resolve_block(resolver, finally);
+            }
+        }
+    }
+}
+
+fn resolve_members(resolver: &mut Resolver, fields: &[Field], methods: &mut [Function]) {
+    for field in fields {
+        resolver.line = field.line;
+        if let Some(initializer) = &field.initializer {
+            resolve_expression(resolver, initializer);
+        }
+    }
+
+    for method in methods {
+        resolve_function(resolver, method);
+    }
+}
+
+/// Resolves the parameters of a function, its body is resolved by `resolve_bodies`.
+fn resolve_function(resolver: &mut Resolver, function: &mut Function) {
+    resolver.line = function.line;
+
+    // default values are computed once, like the initializer of a const
+    for parameter in &mut function.parameters {
+        if let Some(default) = &mut parameter.default {
+            resolve_expression(resolver, default);
+            match evaluate(resolver, default) {
+                Some(constant) => *default = constant,
+                None => resolver.error(format!(
+                    "default value of parameter '{}' must be a constant expression",
+                    parameter.name
+                )),
+            }
+        }
+    }
+}
+
+fn resolve_body(resolver: &mut Resolver, function: &mut Function) {
+    resolver.line = function.line;
+    resolver.scopes.push(HashMap::new());
+    for parameter in &function.parameters {
+        declare_variable(resolver, &parameter.name);
+    }
+    resolve_block(resolver, &mut function.body);
+    resolver.scopes.pop();
+}
+
+fn declare_variable(resolver: &mut Resolver, name: &str) {
+    let declaration = Declaration {
+        mutability: Mutability::Var,
+        line: resolver.line,
+        value: None,
+    };
+    resolver.declare(name, declaration);
+}
+
+fn declare_pattern(resolver: &mut Resolver, pattern: &Pattern, mutability: Mutability) {
+    for name in pattern.bindings() {
+        let declaration = Declaration {
+            mutability,
+            line: resolver.line,
+            value: None,
+        };
+        resolver.declare(name, declaration);
+    }
+}
+
+/// Reports an assignment to a variable that can't be assigned again.
+fn resolve_assignment(resolver: &mut Resolver, name: &str) {
+    let (mutability, line) = match resolver.lookup(name) {
+        Some(declaration) if declaration.mutability != Mutability::Var => (declaration.mutability, declaration.line),
+        _ => return,
+    };
+
+    resolver.error(format!(
+        "can't assign to {} variable '{}' declared on line {}",
+        mutability, name, line
+    ));
+}
+
+/// Reports an assignment to an element of a `const` list. Only changes made through
+/// the name of the const are seen, not those made by methods or through a copy.
+fn resolve_mutation(resolver: &mut Resolver, object: &Expression) {
+    let name = match object {
+        Expression::Variable(name) => name,
+        Expression::Index(object, _) | Expression::Get(object, _) | Expression::Grouping(object) => {
+            return resolve_mutation(resolver, object)
+        }
+        _ => return,
+    };
+
+    let line = match resolver.lookup(name) {
+        Some(declaration) if declaration.mutability == Mutability::Const => declaration.line,
+        _ => return,
+    };
+
+    resolver.error(format!(
+        "can't change an element of const variable '{}' declared on line {}",
+        name, line
+    ));
+}
+
+fn resolve_expression(resolver: &mut Resolver, expression: &Expression) {
+    match expression {
+        Expression::String(_)
+        | Expression::Boolean(_)
+        | Expression::Number(_)
+        | Expression::Integer(_)
+        | Expression::Nil
+        | Expression::Variable(_)
+        | Expression::This
+        | Expression::Super
+        | Expression::CascadeReceiver => {}
+        Expression::Assign(name, value) => {
+            resolve_expression(resolver, value);
+            resolve_assignment(resolver, name);
+        }
+        Expression::CompoundAssign(target, _, value) => {
+            resolve_expression(resolver, target);
+            resolve_expression(resolver, value);
+            match &**target {
+                Expression::Variable(name) => resolve_assignment(resolver, name),
+                Expression::Index(object, _) => resolve_mutation(resolver, object),
+                _ => {}
+            }
+        }
+        Expression::Postfix(target, _) => {
+            resolve_expression(resolver, target);
+            match &**target {
+                Expression::Variable(name) => resolve_assignment(resolver, name),
+                Expression::Index(object, _) => resolve_mutation(resolver, object),
+                _ => {}
+            }
+        }
+        Expression::Destructure(pattern, value) => {
+            resolve_expression(resolver, value);
+            for name in pattern.bindings() {
+                resolve_assignment(resolver, name);
+            }
+        }
+        Expression::Binary(left, _, right) | Expression::Logical(left, _, right) | Expression::Range(left, _, right) => {
+            resolve_expression(resolver, left);
+            resolve_expression(resolver, right);
+        }
+        Expression::Grouping(expression)
+        | Expression::Unary(_, expression)
+        | Expression::Get(expression, _)
+        | Expression::NullAwareGet(expression, _)
+        | Expression::NullAwareChain(expression)
+        | Expression::TypeCheck(expression, _, _)
+        | Expression::Throw(expression)
+        | Expression::Await(expression)
+        | Expression::Spread(expression) => resolve_expression(resolver, expression),
+        Expression::List(elements) => {
+            for element in elements {
+                resolve_expression(resolver, element);
+            }
+        }
+        Expression::Record(positional, named) => {
+            for field in positional.iter().chain(named.iter().map(|(_, field)| field)) {
+                resolve_expression(resolver, field);
+            }
+        }
+        Expression::Call(callee, arguments, named) => {
+            resolve_expression(resolver, callee);
+            for argument in arguments.iter().chain(named.iter().map(|(_, argument)| argument)) {
+                resolve_expression(resolver, argument);
+            }
+        }
+        Expression::Set(object, _, value) => {
+            resolve_expression(resolver, object);
+            resolve_expression(resolver, value);
+        }
+        Expression::Index(object, index) | Expression::NullAwareIndex(object, index) => {
+            resolve_expression(resolver, object);
+            resolve_expression(resolver, index);
+        }
+        Expression::SetIndex(object, index, value) => {
+            resolve_expression(resolver, object);
+            resolve_expression(resolver, index);
+            resolve_expression(resolver, value);
+            resolve_mutation(resolver, object);
+        }
+        Expression::Cascade(receiver, sections) => {
+            resolve_expression(resolver, receiver);
+            for section in sections {
+                resolve_expression(resolver, section);
+            }
+        }
+        Expression::Ternary(condition, then_branch, else_branch) => {
+            resolve_expression(resolver, condition);
+            resolve_expression(resolver, then_branch);
+            resolve_expression(resolver, else_branch);
+        }
+        Expression::Switch(value, arms) => {
+            resolve_expression(resolver, value);
+
+            for arm in arms {
+                resolver.scopes.push(HashMap::new());
+                declare_pattern(resolver, &arm.pattern, Mutability::Var);
+                if let Some(guard) = &arm.guard {
+                    resolve_expression(resolver, guard);
+                }
+                resolve_expression(resolver, &arm.body);
+                resolver.scopes.pop();
+            }
+        }
+    }
+}
+
+/// Computes a constant expression down to a literal, `None` when it is not constant.
+fn evaluate(resolver: &Resolver, expression: &Expression) -> Option<Expression> {
+    match expression {
+        Expression::String(_)
+        | Expression::Boolean(_)
+        | Expression::Number(_)
+        | Expression::Integer(_)
+        | Expression::Nil => Some(expression.clone()),
+        Expression::Grouping(expression) => evaluate(resolver, expression),
+        Expression::Variable(name) => resolver.lookup(name)?.value.clone(),
+        Expression::List(elements) => elements
+            .iter()
+            .map(|element| evaluate(resolver, element))
+            .collect::<Option<Vec<Expression>>>()
+            .map(Expression::List),
+        Expression::Record(positional, named) => {
+            let positional = positional
+                .iter()
+                .map(|field| evaluate(resolver, field))
+                .collect::<Option<Vec<Expression>>>()?;
+            let named = named
+                .iter()
+                .map(|(name, field)| Some((name.clone(), evaluate(resolver, field)?)))
+                .collect::<Option<Vec<(String, Expression)>>>()?;
+            Some(Expression::Record(positional, named))
+        }
+        Expression::Unary(operator, right) => match (operator, evaluate(resolver, right)?) {
+            (UnaryOperator::Minus, Expression::Number(number)) => Some(Expression::Number(-number)),
+            (UnaryOperator::Minus, Expression::Integer(integer)) => integer.checked_neg().map(Expression::Integer),
+            (UnaryOperator::Bang, right) => Some(Expression::Boolean(!is_truthy(&right))),
+            (UnaryOperator::Tilde, Expression::Integer(integer)) => Some(Expression::Integer(!integer)),
+            _ => None,
+        },
+        Expression::Binary(left, operator, right) => {
+            evaluate_binary(evaluate(resolver, left)?, operator, evaluate(resolver, right)?)
+        }
+        Expression::Logical(left, operator, right) => {
+            let left = evaluate(resolver, left)?;
+            let short_circuits = match operator {
+                LogicalOperator::And => !is_truthy(&left),
+                LogicalOperator::Or => is_truthy(&left),
+                LogicalOperator::QuestionQuestion => left != Expression::Nil,
+            };
+
+            match short_circuits {
+                true => Some(left),
+                false => evaluate(resolver, right),
+            }
+        }
+        Expression::Ternary(condition, then_branch, else_branch) => match evaluate(resolver, condition)? {
+            Expression::Boolean(true) => evaluate(resolver, then_branch),
+            Expression::Boolean(false) => evaluate(resolver, else_branch),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_truthy(value: &Expression) -> bool {
+    !matches!(value, Expression::Nil | Expression::Boolean(false))
+}
+
+fn evaluate_binary(left: Expression, operator: &BinaryOperator, right: Expression) -> Option<Expression> {
+    let value = match (left, right) {
+        (Expression::Integer(left), Expression::Integer(right)) => evaluate_integer(left, operator, right)?,
+        // an int next to a double is computed as a double, so `1 == 1.0`
+        (Expression::Integer(left), Expression::Number(right)) => evaluate_arithmetic(left as f64, operator, right)?,
+        (Expression::Number(left), Expression::Integer(right)) => evaluate_arithmetic(left, operator, right as f64)?,
+        (Expression::Number(left), Expression::Number(right)) => evaluate_arithmetic(left, operator, right)?,
+        (left, right) if *operator == BinaryOperator::EqualEqual => Expression::Boolean(left == right),
+        (left, right) if *operator == BinaryOperator::BangEqual => Expression::Boolean(left != right),
+        (Expression::String(left), Expression::String(right)) if *operator == BinaryOperator::Plus => {
+            Expression::String(left + &right)
+        }
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// Folds an operation on two ints exactly, leaving an overflow to the runtime.
+fn evaluate_integer(left: i64, operator: &BinaryOperator, right: i64) -> Option<Expression> {
+    let integer = match operator {
+        BinaryOperator::Plus => left.checked_add(right)?,
+        BinaryOperator::Minus => left.checked_sub(right)?,
+        BinaryOperator::Star => left.checked_mul(right)?,
+        BinaryOperator::Percent => left.checked_rem_euclid(right)?,
+        BinaryOperator::StarStar if right >= 0 => left.checked_pow(u32::try_from(right).ok()?)?,
+        BinaryOperator::Ampersand => left & right,
+        BinaryOperator::Pipe => left | right,
+        BinaryOperator::Caret => left ^ right,
+        // a count of 64 or more shifts every bit out, a negative count is left to the runtime
+        BinaryOperator::LessLess if right >= 0 => left.checked_shl(right as u32).unwrap_or(0),
+        BinaryOperator::GreaterGreater if right >= 0 => {
+            let fill = if left < 0 { -1 } else { 0 };
+            left.checked_shr(right as u32).unwrap_or(fill)
+        }
+        BinaryOperator::GreaterGreaterGreater if right >= 0 => {
+            (left as u64).checked_shr(right as u32).unwrap_or(0) as i64
+        }
+        BinaryOperator::LessLess | BinaryOperator::GreaterGreater | BinaryOperator::GreaterGreaterGreater => {
+            return None
+        }
+        _ => return evaluate_arithmetic(left as f64, operator, right as f64),
+    };
+
+    Some(Expression::Integer(integer))
+}
+
+fn evaluate_arithmetic(left: f64, operator: &BinaryOperator, right: f64) -> Option<Expression> {
+    let number = match operator {
+        BinaryOperator::Plus => left + right,
+        BinaryOperator::Minus => left - right,
+        BinaryOperator::Star => left * right,
+        BinaryOperator::Slash => left / right,
+        BinaryOperator::Percent if right != 0.0 => left.rem_euclid(right),
+        BinaryOperator::StarStar => left.powf(right),
+        BinaryOperator::EqualEqual => return Some(Expression::Boolean(left == right)),
+        BinaryOperator::BangEqual => return Some(Expression::Boolean(left != right)),
+        BinaryOperator::Greater => return Some(Expression::Boolean(left > right)),
+        BinaryOperator::GreaterEqual => return Some(Expression::Boolean(left >= right)),
+        BinaryOperator::Less => return Some(Expression::Boolean(left < right)),
+        BinaryOperator::LessEqual => return Some(Expression::Boolean(left <= right)),
+        _ => return None,
+    };
+
+    // an infinity or NaN, as from `10 / 0`, is left to the runtime
+    if !number.is_finite() {
+        return None;
+    }
+
+    Some(Expression::Number(number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_program, Resolver};
+    use crate::{
+        diagnostic::Diagnostic,
+        expression::Expression,
+        parser::{parse_program, Parser},
+        scanner::Scanner,
+        statement::{Mutability, Statement},
+    };
+
+    fn resolve_source(source: &str) -> (Vec<Statement>, Vec<Diagnostic>) {
+        let mut scanner = Scanner::new(source.to_string());
+        let mut parser = Parser::new(scanner.scan().clone());
+        let mut statements = parse_program(&mut parser);
+        assert_eq!(parser.diagnostics, vec![]);
+
+        let mut resolver = Resolver::new();
+        resolve_program(&mut resolver, &mut statements);
+        (statements, resolver.diagnostics)
+    }
+
+    #[test]
+    fn test_final_reassignment() {
+        let (_, diagnostics) = resolve_source(
+            "final count = 1;\ncount = 2;\ncount++;\n{\n  var count = 0;\n  count += 1;\n}\nfunc f() {\n  [count, other] = [1, 2];\n}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(2, "can't assign to final variable 'count' declared on line 1".to_string()),
+                Diagnostic::new(3, "can't assign to final variable 'count' declared on line 1".to_string()),
+                Diagnostic::new(9, "can't assign to final variable 'count' declared on line 1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_later_declarations() {
+        let (_, diagnostics) = resolve_source(
+            "func f() {\n  x = 1;\n}\nclass C {\n  reset() { LIMIT = 0; }\n}\nfinal x = 0;\nconst LIMIT = 10;\n{\n  y = 1;\n  final y = 2;\n}",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(2, "can't assign to final variable 'x' declared on line 7".to_string()),
+                Diagnostic::new(5, "can't assign to const variable 'LIMIT' declared on line 8".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_const_elements() {
+        let (_, diagnostics) = resolve_source(
+            "const L = [1, [2]];\nL[0] = 3;\nL[1][0] += 1;\nL[0]++;\nvar copy = L;\ncopy[0] = 3;",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(2, "can't change an element of const variable 'L' declared on line 1".to_string()),
+                Diagnostic::new(3, "can't change an element of const variable 'L' declared on line 1".to_string()),
+                Diagnostic::new(4, "can't change an element of const variable 'L' declared on line 1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_const_evaluation() {
+        let (statements, diagnostics) = resolve_source(
+            "const RATE = 2.5;\nconst DOUBLE = (RATE * 2 > 4) ? RATE * 2 : nil;\nconst FLAGS = 1 << 4 | 1;\nconst NAME = \"quill\" + \"script\";\nconst NOW = clock();\nRATE = 3;",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(5, "const variable 'NOW' must be initialized with a constant expression".to_string()),
+                Diagnostic::new(6, "can't assign to const variable 'RATE' declared on line 1".to_string()),
+            ]
+        );
+
+        let initializers: Vec<Expression> = statements
+            .into_iter()
+            .filter_map(|statement| match statement {
+                Statement::Var(Mutability::Const, _, _, initializer, _) => initializer,
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            initializers[..4],
+            [
+                Expression::Number(2.5),
+                Expression::Number(5.0),
+                Expression::Integer(17),
+                Expression::String("quillscript".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shift_evaluation() {
+        let (statements, diagnostics) = resolve_source(
+            "const WIDE = 1 << 64;\nconst NEGATIVE = 1 << -1;\nconst LOGICAL = 5 >>> 70;\nconst SIGN = -8 >> 100;\nconst TOP = 1 << 63;",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(
+                2,
+                "const variable 'NEGATIVE' must be initialized with a constant expression".to_string()
+            )]
+        );
+
+        let initializers: Vec<Option<Expression>> = statements
+            .into_iter()
+            .map(|statement| match statement {
+                Statement::Var(_, _, _, initializer, _) => initializer,
+                statement => panic!("expected a variable, got {:?}", statement),
+            })
+            .collect();
+
+        assert_eq!(initializers[0], Some(Expression::Integer(0)));
+        assert!(!matches!(initializers[1], Some(Expression::Integer(_))));
+        assert_eq!(initializers[2], Some(Expression::Integer(0)));
+        assert_eq!(initializers[3], Some(Expression::Integer(-1)));
+        assert_eq!(initializers[4], Some(Expression::Integer(i64::MIN)));
+    }
+
+    #[test]
+    fn test_folding_limits() {
+        let (statements, diagnostics) = resolve_source(
+            "const HALF = -1 >>> 1;\nconst LARGE = 9007199254740993 + 0;\nconst RATIO = 10 / 0;\nconst PICK = 1 ? 2 : 3;\nconst SUM = 9223372036854775807 + 1;",
+        );
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(3, "const variable 'RATIO' must be initialized with a constant expression".to_string()),
+                Diagnostic::new(4, "const variable 'PICK' must be initialized with a constant expression".to_string()),
+                Diagnostic::new(5, "const variable 'SUM' must be initialized with a constant expression".to_string()),
+            ]
+        );
+
+        let initializers: Vec<Option<Expression>> = statements
+            .into_iter()
+            .map(|statement| match statement {
+                Statement::Var(_, _, _, initializer, _) => initializer,
+                statement => panic!("expected a variable, got {:?}", statement),
+            })
+            .collect();
+
+        assert_eq!(initializers[0], Some(Expression::Integer(i64::MAX)));
+        assert_eq!(initializers[1], Some(Expression::Integer(9007199254740993)));
+    }
+
+    #[test]
+    fn test_parameter_defaults() {
+        let (statements, diagnostics) = resolve_source("func f(a, {b = 1 + 1, c = clock()}) {}");
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(1, "default value of parameter 'c' must be a constant expression".to_string())]
+        );
+
+        match &statements[0] {
+            Statement::Function(function) => assert_eq!(function.parameters[1].default, Some(Expression::Integer(2))),
+            statement => panic!("expected a function, got {:?}", statement),
+        }
+    }
+}
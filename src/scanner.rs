@@ -13,6 +13,7 @@ impl Scanner {
         }
     }
 
+    #[allow(clippy::needless_return)]
     pub fn scan(&mut self) -> &Vec<Token> {
         let mut line = 1;
 
@@ -23,32 +24,98 @@ impl Scanner {
                 ')' => Token::RightParen,
                 '{' => Token::LeftBrace,
                 '}' => Token::RightBrace,
+                '[' => Token::LeftBracket,
+                ']' => Token::RightBracket,
                 ',' => Token::Comma,
-                '.' => Token::Dot,
-                '-' => Token::Minus,
-                '+' => Token::Plus,
+                '.' => match char_indices.next_if_eq(&(position + 1, '.')) {
+                    Some(_) => match char_indices.next_if_eq(&(position + 2, '=')) {
+                        Some(_) => Token::DotDotEqual,
+                        None => match char_indices.next_if_eq(&(position + 2, '.')) {
+                            Some(_) => Token::DotDotDot,
+                            None => Token::DotDot,
+                        },
+                    },
+                    None => Token::Dot,
+                },
+                '-' => match char_indices.next_if_eq(&(position + 1, '-')) {
+                    Some(_) => Token::MinusMinus,
+                    None => match char_indices.next_if_eq(&(position + 1, '=')) {
+                        Some(_) => Token::MinusEqual,
+                        None => Token::Minus,
+                    },
+                },
+                '+' => match char_indices.next_if_eq(&(position + 1, '+')) {
+                    Some(_) => Token::PlusPlus,
+                    None => match char_indices.next_if_eq(&(position + 1, '=')) {
+                        Some(_) => Token::PlusEqual,
+                        None => Token::Plus,
+                    },
+                },
                 ';' => Token::Semicolon,
-                '*' => Token::Star,
+                ':' => Token::Colon,
+                '*' => match char_indices.next_if_eq(&(position + 1, '*')) {
+                    Some(_) => Token::StarStar,
+                    None => match char_indices.next_if_eq(&(position + 1, '=')) {
+                        Some(_) => Token::StarEqual,
+                        None => Token::Star,
+                    },
+                },
+                '%' => match char_indices.next_if_eq(&(position + 1, '=')) {
+                    Some(_) => Token::PercentEqual,
+                    None => Token::Percent,
+                },
+                '&' => Token::Ampersand,
+                '|' => Token::Pipe,
+                '^' => Token::Caret,
+                '~' => Token::Tilde,
+                '?' => match char_indices.next_if_eq(&(position + 1, '?')) {
+                    Some(_) => match char_indices.next_if_eq(&(position + 2, '=')) {
+                        Some(_) => Token::QuestionQuestionEqual,
+                        None => Token::QuestionQuestion,
+                    },
+                    None => match char_indices.next_if_eq(&(position + 1, '.')) {
+                        Some(_) => Token::QuestionDot,
+                        None => match char_indices.next_if_eq(&(position + 1, '[')) {
+                            Some(_) => Token::QuestionLeftBracket,
+                            None => Token::Question,
+                        },
+                    },
+                },
                 '!' => match char_indices.next_if_eq(&(position + 1, '=')) {
                     Some(_) => Token::BangEqual,
                     None => Token::Bang,
                 },
                 '=' => match char_indices.next_if_eq(&(position + 1, '=')) {
                     Some(_) => Token::EqualEqual,
-                    None => Token::Equal,
+                    None => match char_indices.next_if_eq(&(position + 1, '>')) {
+                        Some(_) => Token::EqualGreater,
+                        None => Token::Equal,
+                    },
                 },
-                '<' => match char_indices.next_if_eq(&(position + 1, '=')) {
-                    Some(_) => Token::LessEqual,
-                    None => Token::Less,
+                '<' => match char_indices.next_if_eq(&(position + 1, '<')) {
+                    Some(_) => Token::LessLess,
+                    None => match char_indices.next_if_eq(&(position + 1, '=')) {
+                        Some(_) => Token::LessEqual,
+                        None => Token::Less,
+                    },
                 },
-                '>' => match char_indices.next_if_eq(&(position + 1, '=')) {
-                    Some(_) => Token::GreaterEqual,
-                    None => Token::Greater,
+                '>' => match char_indices.next_if_eq(&(position + 1, '>')) {
+                    Some(_) => match char_indices.next_if_eq(&(position + 2, '>')) {
+                        Some(_) => Token::GreaterGreaterGreater,
+                        None => Token::GreaterGreater,
+                    },
+                    None => match char_indices.next_if_eq(&(position + 1, '=')) {
+                        Some(_) => Token::GreaterEqual,
+                        None => Token::Greater,
+                    },
                 },
                 // TODO : parse comment
                 '/' => match char_indices.next_if_eq(&(position + 1, '/')) {
                     Some(_) => Token::Slash,
-                    None => Token::Slash,
+                    None => match char_indices.next_if_eq(&(position + 1, '=')) {
+                        Some(_) => Token::SlashEqual,
+                        None => Token::Slash,
+                    },
                 },
                 '"' => {
                     let mut last_matched: char = '\0';
@@ -72,6 +139,20 @@ impl Scanner {
                         ),
                     }
                 }
+                // `$1`, `$2`... name the positional fields of a record, only after `.` or `?.`
+                '$' => {
+                    let mut field = String::from("$");
+                    if matches!(self.tokens.last(), Some(Token::Dot) | Some(Token::QuestionDot)) {
+                        while let Some((_, digit)) = char_indices.next_if(|(_pos, c)| self.is_digit(*c)) {
+                            field.push(digit);
+                        }
+                    }
+
+                    match field.len() > 1 {
+                        true => Token::Identifier(field),
+                        false => Token::Invalid(line, position as i32, "Unrecognized token".to_string()),
+                    }
+                }
                 ' ' => continue,
                 '\n' => {
                     line += 1;
@@ -84,12 +165,34 @@ impl Scanner {
                         number.push(char);
 
                         while let Some((_, num)) =
-                            char_indices.next_if(|(_pos, c)| self.is_digit(*c) || *c == '.')
+                            char_indices.next_if(|(_pos, c)| self.is_digit(*c))
                         {
                             number.push(num);
                         }
 
-                        Token::Number(number.parse::<f64>().unwrap())
+                        // only treat the dot as a decimal point when a digit follows,
+                        // so that `0..10` scans as a range
+                        let mut lookahead = char_indices.clone();
+                        let mut integer = true;
+                        if let (Some((_, '.')), Some((_, c))) = (lookahead.next(), lookahead.next()) {
+                            if self.is_digit(c) {
+                                char_indices.next();
+                                number.push('.');
+                                integer = false;
+
+                                while let Some((_, num)) =
+                                    char_indices.next_if(|(_pos, c)| self.is_digit(*c))
+                                {
+                                    number.push(num);
+                                }
+                            }
+                        }
+
+                        // an integer too large for 64 bits is kept as a double
+                        match number.parse::<i64>() {
+                            Ok(value) if integer => Token::Integer(value),
+                            _ => Token::Number(number.parse::<f64>().unwrap()),
+                        }
                     } else if self.is_alpha(char) {
                         let mut identifier = String::new();
 
@@ -107,14 +210,38 @@ impl Scanner {
                             "and" => Token::And,
                             "or" => Token::Or,
                             "if" => Token::If,
+                            "import" => Token::Import,
+                            "export" => Token::Export,
                             "else" => Token::Else,
                             "for" => Token::For,
+                            "in" => Token::In,
+                            "is" => Token::Is,
+                            "as" => Token::As,
+                            "assert" => Token::Assert,
+                            "async" => Token::Async,
+                            "await" => Token::Await,
                             "while" => Token::While,
+                            "yield" => Token::Yield,
                             "print" => Token::Print,
                             "var" => Token::Var,
+                            "final" => Token::Final,
+                            "const" => Token::Const,
                             "func" => Token::Func,
                             "return" => Token::Return,
                             "class" => Token::Class,
+                            "enum" => Token::Enum,
+                            "extends" => Token::Extends,
+                            "this" => Token::This,
+                            "break" => Token::Break,
+                            "continue" => Token::Continue,
+                            "switch" => Token::Switch,
+                            "case" => Token::Case,
+                            "default" => Token::Default,
+                            "when" => Token::When,
+                            "throw" => Token::Throw,
+                            "try" => Token::Try,
+                            "catch" => Token::Catch,
+                            "finally" => Token::Finally,
                             "super" => Token::Super,
                             "nil" => Token::Nil,
                             _ => Token::Identifier(identifier),
@@ -134,10 +261,12 @@ impl Scanner {
         self.is_alpha(char) || self.is_digit(char)
     }
 
+    #[allow(clippy::manual_range_contains)]
     fn is_digit(&self, char: char) -> bool {
         char >= '0' && char <= '9'
     }
 
+    #[allow(clippy::manual_range_contains)]
     fn is_alpha(&self, char: char) -> bool {
         char >= 'a' && char <= 'z' || char >= 'A' && char <= 'Z' || char == '_'
     }
@@ -156,10 +285,11 @@ mod tests {
 
         let number_token = tokens.first().unwrap();
 
-        assert_eq!(number_token, &Token::Number(1283293 as f64))
+        assert_eq!(number_token, &Token::Integer(1283293))
     }
 
     #[test]
+    #[allow(clippy::unnecessary_cast)]
     fn test_floating_number() {
         let source = String::from("1283.293");
         let mut scanner = Scanner::new(source);
@@ -171,6 +301,15 @@ mod tests {
         assert_eq!(number_token, &Token::Number(1283.293 as f64))
     }
 
+    #[test]
+    fn test_whole_floating_number() {
+        let mut scanner = Scanner::new(String::from("1.0"));
+
+        let tokens = scanner.scan();
+
+        assert_eq!(tokens.first().unwrap(), &Token::Number(1.0))
+    }
+
     #[test]
     fn test_unterminated_string() {
         let source = String::from("\"the unterminated string ");
@@ -197,4 +336,168 @@ mod tests {
 
         assert_eq!(number_token, &Token::String("a string".to_string()))
     }
+
+    #[test]
+    fn test_range() {
+        let source = String::from("0..10 0..=10");
+        let mut scanner = Scanner::new(source);
+
+        let tokens = scanner.scan();
+
+        assert_eq!(
+            tokens,
+            &vec![
+                Token::Integer(0),
+                Token::DotDot,
+                Token::Integer(10),
+                Token::Integer(0),
+                Token::DotDotEqual,
+                Token::Integer(10),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_spread() {
+        let source = String::from("f(...args) a..b");
+        let mut scanner = Scanner::new(source);
+
+        let tokens = scanner.scan();
+
+        assert_eq!(
+            tokens,
+            &vec![
+                Token::Identifier("f".to_string()),
+                Token::LeftParen,
+                Token::DotDotDot,
+                Token::Identifier("args".to_string()),
+                Token::RightParen,
+                Token::Identifier("a".to_string()),
+                Token::DotDot,
+                Token::Identifier("b".to_string()),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_for_in() {
+        let source = String::from("for (var x in xs)");
+        let mut scanner = Scanner::new(source);
+
+        let tokens = scanner.scan();
+
+        assert_eq!(
+            tokens,
+            &vec![
+                Token::For,
+                Token::LeftParen,
+                Token::Var,
+                Token::Identifier("x".to_string()),
+                Token::In,
+                Token::Identifier("xs".to_string()),
+                Token::RightParen,
+            ]
+        )
+    }
+
+    #[test]
+    fn test_compound_assignment() {
+        let source = String::from("+= -= *= /= %= ??= ++ -- + -");
+        let mut scanner = Scanner::new(source);
+
+        let tokens = scanner.scan();
+
+        assert_eq!(
+            tokens,
+            &vec![
+                Token::PlusEqual,
+                Token::MinusEqual,
+                Token::StarEqual,
+                Token::SlashEqual,
+                Token::PercentEqual,
+                Token::QuestionQuestionEqual,
+                Token::PlusPlus,
+                Token::MinusMinus,
+                Token::Plus,
+                Token::Minus,
+            ]
+        )
+    }
+
+    #[test]
+    fn test_bitwise_operators() {
+        let source = String::from("% ** & | ^ ~ << >> >>> <= >=");
+        let mut scanner = Scanner::new(source);
+
+        let tokens = scanner.scan();
+
+        assert_eq!(
+            tokens,
+            &vec![
+                Token::Percent,
+                Token::StarStar,
+                Token::Ampersand,
+                Token::Pipe,
+                Token::Caret,
+                Token::Tilde,
+                Token::LessLess,
+                Token::GreaterGreater,
+                Token::GreaterGreaterGreater,
+                Token::LessEqual,
+                Token::GreaterEqual,
+            ]
+        )
+    }
+
+    #[test]
+    fn test_null_aware_operators() {
+        let source = String::from("a ?? b?.c?[0] ??= nil");
+        let mut scanner = Scanner::new(source);
+
+        let tokens = scanner.scan();
+
+        assert_eq!(
+            tokens,
+            &vec![
+                Token::Identifier("a".to_string()),
+                Token::QuestionQuestion,
+                Token::Identifier("b".to_string()),
+                Token::QuestionDot,
+                Token::Identifier("c".to_string()),
+                Token::QuestionLeftBracket,
+                Token::Integer(0),
+                Token::RightBracket,
+                Token::QuestionQuestionEqual,
+                Token::Nil,
+            ]
+        )
+    }
+
+    #[test]
+    fn test_record_field() {
+        let source = String::from("pair.$1");
+        let mut scanner = Scanner::new(source);
+
+        let tokens = scanner.scan();
+
+        assert_eq!(
+            tokens,
+            &vec![
+                Token::Identifier("pair".to_string()),
+                Token::Dot,
+                Token::Identifier("$1".to_string()),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_dollar_outside_record_field() {
+        let mut scanner = Scanner::new(String::from("var $x = $1;"));
+
+        let tokens = scanner.scan();
+
+        assert_eq!(tokens[1], Token::Invalid(1, 4, "Unrecognized token".to_string()));
+        assert_eq!(tokens[2], Token::Identifier("x".to_string()));
+        assert_eq!(tokens[4], Token::Invalid(1, 9, "Unrecognized token".to_string()));
+    }
 }
@@ -0,0 +1,220 @@
+use core::fmt;
+
+use crate::{expression::Expression, pattern::Pattern, types::Type};
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Statement {
+    /// Statements evaluating an expression record its line for diagnostics
+    Expression(Expression, i32),
+    Print(Expression, i32),
+    /// `var name: Type = value;`, both the annotation and the value are optional
+    Var(Mutability, String, Option<Type>, Option<Expression>, i32),
+    /// `var [a, b] = value;` or `var {name, age} = value;`, declares every binding
+    /// of the pattern
+    Destructure(Mutability, Pattern, Expression, i32),
+    Block(Vec<Statement>),
+    If(Expression, Box<Statement>, Option<Box<Statement>>),
+    While(Expression, Box<Statement>),
+    /// `for (initializer; condition; increment) body`
+    For(
+        Option<Box<Statement>>,
+        Option<Expression>,
+        Option<Expression>,
+        Box<Statement>,
+    ),
+    /// `for (var name in iterable) body`
+    ///
+    /// Iteration follows the iterator protocol: `iterator()` is called once on
+    /// the iterable, then `next()` is called on the returned iterator before
+    /// each pass and the loop ends when it returns `nil`. Lists, maps (their
+    /// keys), sets, strings (their characters) and ranges implement it natively,
+    /// user classes opt in by defining both methods.
+    ForIn(String, Expression, Box<Statement>, i32),
+    /// `label: loop`, gives a loop a name for `break label` and `continue label`
+    Labeled(String, Box<Statement>),
+    Break(Option<String>),
+    Continue(Option<String>),
+    /// `switch (value) { case pattern: ... default: ... }`
+    ///
+    /// The first case whose pattern matches and whose guard holds runs, then the
+    /// switch is done. Cases without statements share the body of the next one.
+    Switch(Expression, Vec<SwitchCase>, Option<Vec<Statement>>, i32),
+    Function(Function),
+    Return(Option<Expression>, i32),
+    /// `yield value;`, hands the next element of a generator to its iteration
+    Yield(Expression, i32),
+    /// `assert(condition, message);`, the message is optional
+    ///
+    /// When the condition is false, raises a catchable `AssertionError` holding
+    /// the message and the source of the condition, its `to_string()`. Asserts
+    /// disabled for production runs never reach this tree, see `Parser::asserts`.
+    Assert(Expression, Option<Expression>, i32),
+    Class(Class),
+    Enum(Enum),
+    Extension(Extension),
+    Import(Import),
+    /// `export declaration`, makes a top-level declaration visible to the modules importing this one
+    Export(Box<Statement>),
+    Try(Try),
+}
+
+/// The keyword a variable is declared with, which decides whether it can be assigned again
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mutability {
+    Var,
+    /// Assigned once, by its declaration
+    Final,
+    /// Final, with a value the resolver computes at compile time
+    Const,
+}
+
+impl fmt::Display for Mutability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mutability::Var => write!(f, "var"),
+            Mutability::Final => write!(f, "final"),
+            Mutability::Const => write!(f, "const"),
+        }
+    }
+}
+
+/// `func name<T>(parameter: Type): Type { body }`, type parameters and
+/// annotations are optional
+#[derive(Clone, PartialEq, Debug)]
+pub struct Function {
+    pub name: String,
+    pub type_parameters: Vec<String>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<Type>,
+    /// `func* name()`, a call returns a lazy iterable whose iterations run the
+    /// body up to its next `yield`, suspended in between
+    pub generator: bool,
+    /// `async func name()`, a call returns a future completed with the result
+    /// of the body, which suspends at each `await` until the awaited future completes
+    pub asynchronous: bool,
+    pub body: Vec<Statement>,
+    pub line: i32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Parameter {
+    pub name: String,
+    pub annotation: Option<Type>,
+    pub kind: ParameterKind,
+    /// value of a parameter the call leaves out, `nil` when there is none
+    pub default: Option<Expression>,
+}
+
+/// How a call passes the value of a parameter, declared as
+/// `func f(positional, [optional], {named, required name})` or
+/// `func f(positional, ...rest)`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ParameterKind {
+    Positional,
+    /// Positional, may be left out
+    Optional,
+    /// Passed as `name: value`, may be left out
+    Named,
+    /// Passed as `name: value`, always
+    Required,
+    /// `...name`, a list of the positional arguments left over, its annotation
+    /// being the type of the elements
+    Rest,
+}
+
+/// `try { body } catch (exception, stack) { handler } finally { cleanup }`
+///
+/// The handler runs when the body throws, with the thrown value and the stack
+/// trace at the point of the throw, function names and source lines included.
+/// Errors raised by the runtime itself, such as a failed cast or an index out
+/// of range, are caught the same way. The cleanup runs last, however the body
+/// and the handler are left. An exception no handler catches ends the program
+/// and is reported with its stack trace.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Try {
+    pub body: Vec<Statement>,
+    pub catch: Option<Catch>,
+    pub finally: Option<Vec<Statement>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Catch {
+    pub exception: String,
+    pub stack: Option<String>,
+    pub body: Vec<Statement>,
+}
+
+/// `class Name<T> extends Base { var field: Type; method(parameter: Type): Type { body } }`
+///
+/// Calling the class creates an instance, runs the field initializers, then
+/// the `init` method with the arguments of the call.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Class {
+    pub name: String,
+    pub type_parameters: Vec<String>,
+    pub superclass: Option<Type>,
+    pub fields: Vec<Field>,
+    pub methods: Vec<Function>,
+    pub line: i32,
+}
+
+/// `extension Name on Type { method() { body } }`
+///
+/// Adds methods to values of the type, and of its subtypes, where the
+/// declaration is in scope. A member of the type itself takes precedence, and
+/// `this` is the value the method was called on.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Extension {
+    pub name: String,
+    pub on: Type,
+    pub methods: Vec<Function>,
+    pub line: i32,
+}
+
+/// `import "path.ql" as name;` loads a module and binds its exports to `name`,
+/// or without `as`, declares each of them under its own name.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Import {
+    pub path: String,
+    pub alias: Option<String>,
+    /// the names the module exports, once the module loader has loaded it
+    pub exports: Option<Vec<String>>,
+    pub line: i32,
+}
+
+/// `enum Name { value, value(argument); var field: Type; method() { body } }`
+///
+/// Every value is an instance created once, in declaration order, with its
+/// `name` and `index` fields set before `init` runs with the arguments of the
+/// value. `Name.values` lists the values in declaration order.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Enum {
+    pub name: String,
+    pub values: Vec<EnumValue>,
+    pub fields: Vec<Field>,
+    pub methods: Vec<Function>,
+    pub line: i32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct EnumValue {
+    pub name: String,
+    pub arguments: Vec<Expression>,
+    pub named: Vec<(String, Expression)>,
+    pub line: i32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Field {
+    pub name: String,
+    pub annotation: Option<Type>,
+    pub initializer: Option<Expression>,
+    pub line: i32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SwitchCase {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub body: Vec<Statement>,
+}
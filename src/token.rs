@@ -7,43 +7,96 @@ pub enum Token {
     RightParen, 
     LeftBrace, 
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma, 
     Dot,  
+    DotDot,
+    DotDotDot,
+    QuestionDot,
+    QuestionLeftBracket,
+    DotDotEqual,
     Semicolon, 
+    Colon,
+    Question,
  
     Plus,
+    PlusEqual,
+    PlusPlus,
     Minus, 
+    MinusEqual,
+    MinusMinus,
     Slash, 
+    SlashEqual,
     Star,
+    StarEqual,
+    StarStar,
+    Percent,
+    PercentEqual,
+    QuestionQuestion,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
+    QuestionQuestionEqual,
     Bang, 
     BangEqual,
     Equal, 
     EqualEqual,
+    EqualGreater,
     Greater,
     GreaterEqual,
+    GreaterGreater,
+    GreaterGreaterGreater,
     Less, 
     LessEqual,
+    LessLess,
 
     Identifier(String), 
     String(String), 
     Number(f64),
+    /// A number literal written without a decimal point, such as `42`
+    Integer(i64),
 
+    As,
+    Assert,
+    Async,
+    Await,
+    Break,
+    Case,
+    Catch,
     Class,
+    Const,
+    Continue,
+    Default,
     Else, 
+    Enum,
+    Export,
+    Extends,
     False, 
+    Final,
+    Finally,
     Func, 
     For, 
     If,
+    Import,
+    In,
+    Is,
     And, 
     Nil, 
     Or,
     Print, 
     Return, 
     Super, 
+    Switch,
     This, 
+    Throw,
     True, 
+    Try,
     Var, 
+    When,
     While,
+    Yield,
     Eof,
 
     Space,
@@ -51,6 +104,54 @@ pub enum Token {
     Invalid(i32, i32, String)
 }
 
+impl Token {
+    /// Whether the token is a reserved word, which can still name a property, as in `Fiber.yield()`
+    pub fn is_keyword(&self) -> bool {
+        matches!(
+            self,
+            Token::As
+                | Token::Assert
+                | Token::Async
+                | Token::Await
+                | Token::Break
+                | Token::Case
+                | Token::Catch
+                | Token::Class
+                | Token::Const
+                | Token::Continue
+                | Token::Default
+                | Token::Else
+                | Token::Enum
+                | Token::Export
+                | Token::Extends
+                | Token::False
+                | Token::Final
+                | Token::Finally
+                | Token::Func
+                | Token::For
+                | Token::If
+                | Token::Import
+                | Token::In
+                | Token::Is
+                | Token::And
+                | Token::Nil
+                | Token::Or
+                | Token::Print
+                | Token::Return
+                | Token::Super
+                | Token::Switch
+                | Token::This
+                | Token::Throw
+                | Token::True
+                | Token::Try
+                | Token::Var
+                | Token::When
+                | Token::While
+                | Token::Yield
+        )
+    }
+}
+
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -58,40 +159,92 @@ impl fmt::Display for Token {
             Token::RightParen => write!(f, ")"),
             Token::LeftBrace => write!(f, "{{"),
             Token::RightBrace => write!(f, "}}"),
+            Token::LeftBracket => write!(f, "["),
+            Token::RightBracket => write!(f, "]"),
             Token::Comma => write!(f, ","),
             Token::Dot => write!(f, "."),
+            Token::DotDot => write!(f, ".."),
+            Token::DotDotDot => write!(f, "..."),
+            Token::QuestionDot => write!(f, "?."),
+            Token::QuestionLeftBracket => write!(f, "?["),
+            Token::DotDotEqual => write!(f, "..="),
             Token::Minus => write!(f, "-"),
+            Token::MinusEqual => write!(f, "-="),
+            Token::MinusMinus => write!(f, "--"),
             Token::Plus => write!(f, "+"),
+            Token::PlusEqual => write!(f, "+="),
+            Token::PlusPlus => write!(f, "++"),
             Token::Semicolon => write!(f, ";"),
+            Token::Colon => write!(f, ":"),
+            Token::Question => write!(f, "?"),
             Token::Star => write!(f, "*"),
+            Token::StarEqual => write!(f, "*="),
+            Token::StarStar => write!(f, "**"),
+            Token::Percent => write!(f, "%"),
             Token::Slash => write!(f, "/"),
+            Token::SlashEqual => write!(f, "/="),
+            Token::PercentEqual => write!(f, "%="),
+            Token::Ampersand => write!(f, "&"),
+            Token::Pipe => write!(f, "|"),
+            Token::Caret => write!(f, "^"),
+            Token::Tilde => write!(f, "~"),
+            Token::QuestionQuestion => write!(f, "??"),
+            Token::QuestionQuestionEqual => write!(f, "??="),
             Token::Bang => write!(f, "!"),
             Token::BangEqual => write!(f, "!="),
             Token::Equal => write!(f, "="),
             Token::EqualEqual => write!(f, "=="),
+            Token::EqualGreater => write!(f, "=>"),
             Token::Greater =>write!(f, ">"),
             Token::GreaterEqual =>write!(f, ">="),
+            Token::GreaterGreater =>write!(f, ">>"),
+            Token::GreaterGreaterGreater =>write!(f, ">>>"),
             Token::Less =>write!(f, "<"),
             Token::LessEqual =>write!(f, "<="),
+            Token::LessLess =>write!(f, "<<"),
             Token::Identifier(_) =>write!(f, "identifier"),
             Token::String(_) =>write!(f, "string"),
             Token::Number(_) =>write!(f, "number"),
+            Token::Integer(_) => write!(f, "number"),
+            Token::As => write!(f, "as"),
+            Token::Async => write!(f, "async"),
+            Token::Await => write!(f, "await"),
+            Token::Assert => write!(f, "assert"),
+            Token::Break => write!(f, "break"),
+            Token::Case => write!(f, "case"),
+            Token::Catch => write!(f, "catch"),
             Token::Class => write!(f, "class"),
+            Token::Const => write!(f, "const"),
+            Token::Continue => write!(f, "continue"),
+            Token::Default => write!(f, "default"),
             Token::Else =>write!(f, "else"),
+            Token::Enum => write!(f, "enum"),
+            Token::Export => write!(f, "export"),
+            Token::Extends => write!(f, "extends"),
             Token::False =>write!(f, "false"),
+            Token::Final => write!(f, "final"),
+            Token::Finally => write!(f, "finally"),
             Token::Func =>write!(f, "func"),
             Token::For =>write!(f, "for"),
             Token::If =>write!(f, "if"),
+            Token::Import => write!(f, "import"),
+            Token::In =>write!(f, "in"),
+            Token::Is =>write!(f, "is"),
             Token::And => write!(f, "and"),
             Token::Nil =>write!(f, "nil"),
             Token::Or =>write!(f, "or"),
             Token::Print =>write!(f, "print"),
             Token::Return =>write!(f, "return"),
             Token::Super =>write!(f, "super"),
+            Token::Switch =>write!(f, "switch"),
             Token::This =>write!(f, "this"),
+            Token::Throw => write!(f, "throw"),
             Token::True =>write!(f, "true"),
+            Token::Try => write!(f, "try"),
             Token::Var =>write!(f, "var"),
+            Token::When =>write!(f, "when"),
             Token::While =>write!(f, "while"),
+            Token::Yield => write!(f, "yield"),
             Token::Eof =>write!(f, "eof"),
             Token::Invalid(_,_,_) =>write!(f, "invalid"),
             Token::Space => write!(f, "space"),
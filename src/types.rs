@@ -0,0 +1,166 @@
+use core::fmt;
+use std::collections::HashMap;
+
+/// Runtime type descriptors, what `is`, `is!` and `as` compare a value
+/// against and what the `typeof(value)` builtin returns.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Type {
+    /// `dynamic`, every value is a `dynamic`
+    Dynamic,
+    Null,
+    Bool,
+    Int,
+    Double,
+    /// `num`, the supertype of `int` and `double`
+    Num,
+    String,
+    List,
+    Map,
+    Set,
+    Range,
+    /// `Iterable`, the supertype of lists, sets, ranges and what generators return
+    Iterable,
+    /// `Future`, the eventual result of an async function
+    Future,
+    Function,
+    /// A user class, looked up by name
+    Class(String),
+    /// `Type?`, the type or `nil`
+    Nullable(Box<Type>),
+    /// A type parameter of the enclosing generic class or function, such as `T`
+    Parameter(String),
+    /// A generic type with its type arguments, such as `List<int>` or `Box<T>`
+    Generic(Box<Type>, Vec<Type>),
+    /// `(int, String)` or `(x: int, y: int)`, the types of the positional
+    /// fields of a record followed by those of its named fields
+    Record(Vec<Type>, Vec<(String, Type)>),
+}
+
+impl Type {
+    /// The builtin type of that name, any other name is taken for a class and
+    /// the checker reports it when no class is declared with it.
+    pub fn from_name(name: &str) -> Type {
+        match name {
+            "dynamic" => Type::Dynamic,
+            "Null" => Type::Null,
+            "bool" => Type::Bool,
+            "int" => Type::Int,
+            "double" => Type::Double,
+            "num" => Type::Num,
+            "String" => Type::String,
+            "List" => Type::List,
+            "Map" => Type::Map,
+            "Set" => Type::Set,
+            "Range" => Type::Range,
+            "Iterable" => Type::Iterable,
+            "Future" => Type::Future,
+            "Function" => Type::Function,
+            _ => Type::Class(name.to_string()),
+        }
+    }
+
+    /// Whether a value of this type can be stored where `other` is expected,
+    /// `dynamic` on either side always can.
+    pub fn is_assignable_to(&self, other: &Type) -> bool {
+        match (self, other) {
+            (Type::Dynamic, _) | (_, Type::Dynamic) => true,
+            (Type::Int, Type::Num) | (Type::Double, Type::Num) => true,
+            (Type::Null, Type::Nullable(_)) => true,
+            (Type::Nullable(inner), Type::Nullable(other)) => inner.is_assignable_to(other),
+            (Type::Nullable(_), _) => false,
+            (_, Type::Nullable(other)) => self.is_assignable_to(other),
+            // lists and sets are iterables of their elements, ranges of ints
+            (Type::List | Type::Set | Type::Range, Type::Iterable) => true,
+            (Type::Generic(base, _), Type::Iterable) => matches!(**base, Type::List | Type::Set | Type::Iterable),
+            (Type::List | Type::Set, Type::Generic(base, _)) if **base == Type::Iterable => true,
+            (Type::Range, Type::Generic(base, others)) if **base == Type::Iterable => {
+                others.len() == 1 && Type::Int.is_assignable_to(&others[0])
+            }
+            (Type::Generic(base, arguments), Type::Generic(other, others))
+                if **other == Type::Iterable && matches!(**base, Type::List | Type::Set) =>
+            {
+                arguments.len() == others.len()
+                    && arguments.iter().zip(others).all(|(argument, other)| argument.is_assignable_to(other))
+            }
+            // type arguments are covariant, and a type without them takes any
+            (Type::Generic(base, arguments), Type::Generic(other, others)) => {
+                base == other
+                    && arguments.len() == others.len()
+                    && arguments.iter().zip(others).all(|(argument, other)| argument.is_assignable_to(other))
+            }
+            (Type::Generic(base, _), other) | (other, Type::Generic(base, _)) => **base == *other,
+            // named fields match by name, whatever their order
+            (Type::Record(positional, named), Type::Record(other_positional, other_named)) => {
+                positional.len() == other_positional.len()
+                    && named.len() == other_named.len()
+                    && positional
+                        .iter()
+                        .zip(other_positional)
+                        .all(|(field, other)| field.is_assignable_to(other))
+                    && named.iter().all(|(name, field)| {
+                        other_named
+                            .iter()
+                            .any(|(other_name, other)| name == other_name && field.is_assignable_to(other))
+                    })
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Replaces the type parameters bound in `bindings` by their types.
+    pub fn substitute(&self, bindings: &HashMap<String, Type>) -> Type {
+        match self {
+            Type::Parameter(name) => bindings.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Type::Nullable(inner) => Type::Nullable(Box::new(inner.substitute(bindings))),
+            Type::Generic(base, arguments) => Type::Generic(
+                base.clone(),
+                arguments.iter().map(|argument| argument.substitute(bindings)).collect(),
+            ),
+            Type::Record(positional, named) => Type::Record(
+                positional.iter().map(|field| field.substitute(bindings)).collect(),
+                named
+                    .iter()
+                    .map(|(name, field)| (name.clone(), field.substitute(bindings)))
+                    .collect(),
+            ),
+            _ => self.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Dynamic => write!(f, "dynamic"),
+            Type::Null => write!(f, "Null"),
+            Type::Bool => write!(f, "bool"),
+            Type::Int => write!(f, "int"),
+            Type::Double => write!(f, "double"),
+            Type::Num => write!(f, "num"),
+            Type::String => write!(f, "String"),
+            Type::List => write!(f, "List"),
+            Type::Map => write!(f, "Map"),
+            Type::Set => write!(f, "Set"),
+            Type::Range => write!(f, "Range"),
+            Type::Iterable => write!(f, "Iterable"),
+            Type::Future => write!(f, "Future"),
+            Type::Function => write!(f, "Function"),
+            Type::Class(name) => write!(f, "{}", name),
+            Type::Nullable(inner) => write!(f, "{}?", inner),
+            Type::Parameter(name) => write!(f, "{}", name),
+            Type::Generic(base, arguments) => {
+                let arguments: Vec<String> = arguments.iter().map(|argument| argument.to_string()).collect();
+                write!(f, "{}<{}>", base, arguments.join(", "))
+            }
+            Type::Record(positional, named) => {
+                let mut fields: Vec<String> = positional.iter().map(|field| field.to_string()).collect();
+                fields.extend(named.iter().map(|(name, field)| format!("{}: {}", name, field)));
+
+                match (positional.len(), named.len()) {
+                    (1, 0) => write!(f, "({},)", fields[0]),
+                    _ => write!(f, "({})", fields.join(", ")),
+                }
+            }
+        }
+    }
+}